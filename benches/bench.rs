@@ -1,6 +1,10 @@
+use asynk_strim::Yielder;
 use divan::{black_box, black_box_drop, AllocProfiler};
-use futures_lite::stream;
-use std::pin::pin;
+use futures_lite::{stream, StreamExt};
+use std::{
+    future::Future,
+    pin::{pin, Pin},
+};
 
 #[global_allocator]
 static GLOBAL: AllocProfiler<mimalloc::MiMalloc> = AllocProfiler::new(mimalloc::MiMalloc);
@@ -40,6 +44,67 @@ fn asynk_strim() {
     stream::block_on(stream).for_each(black_box_drop);
 }
 
+#[divan::bench]
+fn asynk_strim_buffered() {
+    let stream = pin!(asynk_strim::buffered_stream_fn::<64, _, _, _>(
+        |yielder| async move {
+            for _ in 0..ITER_COUNT {
+                yielder.yield_item(black_box(1312)).await;
+            }
+        }
+    ));
+
+    stream::block_on(stream).for_each(black_box_drop);
+}
+
+/// Frame-sized item which is expensive to move around
+type Large = [u8; 16 * 1024];
+
+#[divan::bench]
+fn asynk_strim_large() {
+    let stream = pin!(asynk_strim::strim_fn(|mut yielder| async move {
+        for _ in 0..ITER_COUNT {
+            yielder.yield_item(black_box([13; 16 * 1024])).await;
+        }
+    }));
+
+    stream::block_on(stream).for_each(|item: Large| black_box_drop(item));
+}
+
+#[divan::bench]
+fn asynk_strim_large_emplace() {
+    let stream = pin!(asynk_strim::strim_fn(|mut yielder| async move {
+        for _ in 0..ITER_COUNT {
+            yielder.yield_emplace(|| black_box([13; 16 * 1024])).await;
+        }
+    }));
+
+    stream::block_on(stream).for_each(|item: Large| black_box_drop(item));
+}
+
+/// Nest `depth` streams and yield to the outermost one from the innermost one
+fn nested(depth: usize, outer: &mut Yielder<usize>) -> Pin<Box<dyn Future<Output = ()> + '_>> {
+    Box::pin(async move {
+        if depth == 0 {
+            for _ in 0..ITER_COUNT {
+                outer.yield_item(black_box(1312)).await;
+            }
+        } else {
+            let inner = asynk_strim::stream_fn(|_: Yielder<()>| nested(depth - 1, outer));
+            inner.for_each(drop).await;
+        }
+    })
+}
+
+#[divan::bench(args = [1, 8, 32])]
+fn asynk_strim_nested(depth: usize) {
+    let stream = pin!(asynk_strim::strim_fn(|mut yielder| async move {
+        nested(depth - 1, &mut yielder).await;
+    }));
+
+    stream::block_on(stream).for_each(black_box_drop);
+}
+
 fn main() {
     divan::main();
 }
@@ -0,0 +1,225 @@
+use crate::{
+    buffered_yielder::BufferedYielder,
+    stream::{AsynkStrim, StreamState},
+};
+use core::{
+    fmt,
+    future::Future,
+    iter::FusedIterator,
+    marker::PhantomPinned,
+    mem,
+    pin::Pin,
+    task::{self, Poll},
+};
+use futures_core::{FusedStream, Stream};
+
+/// Inline batch of up to `N` items
+///
+/// Handed out by [`BufferedStreamFn::poll_next_chunk`]. Iterating over it yields the items in order.
+pub struct Chunk<Item, const N: usize> {
+    items: [Option<Item>; N],
+    start: usize,
+    end: usize,
+}
+
+impl<Item, const N: usize> Chunk<Item, N> {
+    #[inline]
+    fn new() -> Self {
+        Self {
+            items: core::array::from_fn(|_| None),
+            start: 0,
+            end: 0,
+        }
+    }
+
+    /// Push an item to the back of the chunk
+    ///
+    /// Hands the item back if the chunk is full
+    #[inline]
+    pub(crate) fn push(&mut self, item: Item) -> Result<(), Item> {
+        if self.end == N {
+            return Err(item);
+        }
+
+        self.items[self.end] = Some(item);
+        self.end += 1;
+        Ok(())
+    }
+
+    #[inline]
+    fn is_drained(&self) -> bool {
+        self.start == self.end
+    }
+}
+
+impl<Item, const N: usize> Iterator for Chunk<Item, N> {
+    type Item = Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.is_drained() {
+            return None;
+        }
+
+        let item = self.items[self.start].take();
+        self.start += 1;
+        item
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.end - self.start;
+        (len, Some(len))
+    }
+}
+
+impl<Item, const N: usize> ExactSizeIterator for Chunk<Item, N> {}
+
+impl<Item, const N: usize> FusedIterator for Chunk<Item, N> {}
+
+impl<Item, const N: usize> fmt::Debug for Chunk<Item, N>
+where
+    Item: fmt::Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.items[self.start..self.end].iter().flatten())
+            .finish()
+    }
+}
+
+/// Stream created via [`buffered_stream_fn`](crate::buffered_stream_fn)
+///
+/// The closure is only suspended once the buffer of `N` items is full or it awaits something else.
+pub struct BufferedStreamFn<F, Fut, Item, const N: usize> {
+    state: AsynkStrim<F, Fut, ()>,
+    buffer: Chunk<Item, N>,
+    _pinned: PhantomPinned,
+}
+
+struct BufferedStreamFnProj<'a, F, Fut, Item, const N: usize> {
+    state: Pin<&'a mut AsynkStrim<F, Fut, ()>>,
+    buffer: &'a mut Chunk<Item, N>,
+}
+
+impl<F, Fut, Item, const N: usize> BufferedStreamFn<F, Fut, Item, N> {
+    #[inline]
+    pub(crate) fn new(func: F) -> Self {
+        const {
+            assert!(
+                N > 0,
+                "the buffer needs to be able to hold at least one item"
+            );
+        };
+
+        Self {
+            state: AsynkStrim::Initial { func },
+            buffer: Chunk::new(),
+            _pinned: PhantomPinned,
+        }
+    }
+
+    // `pin-project-lite` doesn't support const generics, so we have to project by hand.
+    #[inline]
+    fn project(self: Pin<&mut Self>) -> BufferedStreamFnProj<'_, F, Fut, Item, N> {
+        // the state is structurally pinned, the buffer isn't.
+        // we never move the state out and don't implement `Drop` or `Unpin`.
+        #[allow(unsafe_code)]
+        unsafe {
+            let this = self.get_unchecked_mut();
+            BufferedStreamFnProj {
+                state: Pin::new_unchecked(&mut this.state),
+                buffer: &mut this.buffer,
+            }
+        }
+    }
+
+    /// Current state of the stream
+    ///
+    /// The stream might still hold buffered items while the generator is finished
+    #[inline]
+    pub fn state(&self) -> StreamState {
+        self.state.state()
+    }
+}
+
+impl<F, Fut, Item, const N: usize> BufferedStreamFn<F, Fut, Item, N>
+where
+    F: FnOnce(BufferedYielder<Item, N>) -> Fut,
+    Fut: Future<Output = ()>,
+{
+    /// Refill the buffer if it is empty
+    ///
+    /// Returns `Ready` once the buffer contains items or the generator has finished
+    #[inline]
+    fn poll_fill(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<()> {
+        let this = self.project();
+        if !this.buffer.is_drained() {
+            return Poll::Ready(());
+        }
+
+        // the buffer is fully drained. start from the front again.
+        *this.buffer = Chunk::new();
+        let poll_output = this.state.poll_step(cx, this.buffer, BufferedYielder::new);
+
+        if poll_output.is_pending() && this.buffer.is_drained() {
+            Poll::Pending
+        } else {
+            Poll::Ready(())
+        }
+    }
+
+    /// Poll for the whole batch of buffered items at once
+    ///
+    /// Returns `None` once the stream is exhausted
+    #[inline]
+    pub fn poll_next_chunk(
+        mut self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+    ) -> Poll<Option<Chunk<Item, N>>> {
+        core::task::ready!(self.as_mut().poll_fill(cx));
+
+        let buffer = self.project().buffer;
+        if buffer.is_drained() {
+            Poll::Ready(None)
+        } else {
+            Poll::Ready(Some(mem::replace(buffer, Chunk::new())))
+        }
+    }
+}
+
+impl<F, Fut, Item, const N: usize> Stream for BufferedStreamFn<F, Fut, Item, N>
+where
+    F: FnOnce(BufferedYielder<Item, N>) -> Fut,
+    Fut: Future<Output = ()>,
+{
+    type Item = Item;
+
+    #[inline]
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Option<Self::Item>> {
+        core::task::ready!(self.as_mut().poll_fill(cx));
+        Poll::Ready(self.project().buffer.next())
+    }
+}
+
+impl<F, Fut, Item, const N: usize> FusedStream for BufferedStreamFn<F, Fut, Item, N>
+where
+    F: FnOnce(BufferedYielder<Item, N>) -> Fut,
+    Fut: Future<Output = ()>,
+{
+    #[inline]
+    fn is_terminated(&self) -> bool {
+        self.buffer.is_drained() && self.state() == StreamState::Finished
+    }
+}
+
+impl<F, Fut, Item, const N: usize> fmt::Debug for BufferedStreamFn<F, Fut, Item, N> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BufferedStreamFn")
+            .field("state", &self.state())
+            .field("buffered", &self.buffer.len())
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,102 @@
+use crate::{buffered::Chunk, waker::StreamId, DynYield};
+use core::{
+    future::Future,
+    marker::PhantomData,
+    pin::Pin,
+    task::{self, Poll},
+};
+
+struct BufferedYieldFuture<Item, const N: usize> {
+    item: Option<Item>,
+    stream_id: StreamId,
+}
+
+impl<Item, const N: usize> Future for BufferedYieldFuture<Item, N> {
+    type Output = ();
+
+    #[inline]
+    fn poll(mut self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Self::Output> {
+        let Some(item) = self.item.take() else {
+            return Poll::Ready(());
+        };
+
+        let frame = crate::waker::find_frame_by_id(cx, self.stream_id)
+            .expect("no matching stream frame found");
+
+        let buffer = {
+            let mut buffer_ptr = frame.out_ref::<Chunk<Item, N>>();
+
+            // the stream always points the frame to its buffer
+            #[allow(unsafe_code)]
+            unsafe {
+                buffer_ptr.as_mut()
+            }
+        };
+
+        match buffer.push(item) {
+            Ok(()) => Poll::Ready(()),
+            Err(item) => {
+                // the buffer is full. suspend until the consumer drained it.
+                self.item = Some(item);
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<Item, const N: usize> Unpin for BufferedYieldFuture<Item, N> {}
+
+/// Handle to allow you to yield something from the buffered stream
+///
+/// Since every yield lands in the buffer, you can clone the handle and yield from several futures at once,
+/// for example from sub-tasks running via `join`.
+pub struct BufferedYielder<Item, const N: usize> {
+    _marker: PhantomData<Item>,
+    stream_id: StreamId,
+}
+
+impl<Item, const N: usize> Clone for BufferedYielder<Item, N> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self::new(self.stream_id)
+    }
+}
+
+impl<Item, const N: usize> BufferedYielder<Item, N> {
+    #[inline]
+    pub(crate) fn new(stream_id: StreamId) -> Self {
+        Self {
+            _marker: PhantomData,
+            stream_id,
+        }
+    }
+
+    /// Yield an item from the stream
+    ///
+    /// Only suspends if the buffer is full. Concurrent yields are queued in the order they are polled in.
+    #[inline]
+    pub async fn yield_item(&self, item: Item) {
+        let future = BufferedYieldFuture::<Item, N> {
+            item: Some(item),
+            stream_id: self.stream_id,
+        };
+
+        future.await;
+    }
+}
+
+impl<Item, const N: usize> DynYield<Item> for BufferedYielder<Item, N> {
+    #[inline]
+    fn poll_yield(&mut self, cx: &mut task::Context<'_>, item: &mut Option<Item>) -> Poll<()> {
+        let mut future = BufferedYieldFuture::<Item, N> {
+            item: item.take(),
+            stream_id: self.stream_id,
+        };
+
+        let poll = Pin::new(&mut future).poll(cx);
+
+        // a full buffer hands the item back to the future
+        *item = future.item;
+        poll
+    }
+}
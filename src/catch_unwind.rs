@@ -0,0 +1,113 @@
+use core::{
+    any::Any,
+    fmt,
+    pin::Pin,
+    task::{self, Poll},
+};
+use futures_core::{FusedStream, Stream};
+use pin_project_lite::pin_project;
+use std::{
+    boxed::Box,
+    panic::{self, AssertUnwindSafe},
+};
+
+/// Payload of a panic caught by [`CatchUnwind`]
+pub type PanicPayload = Box<dyn Any + Send + 'static>;
+
+pin_project! {
+    /// Stream created via [`catch_unwind_stream_fn`](crate::catch_unwind_stream_fn) or [`catch_unwind_try_stream_fn`](crate::catch_unwind_try_stream_fn)
+    ///
+    /// Panics of the wrapped stream are caught and handed to the hook, which may turn them into a final item.
+    /// Afterwards the wrapped stream is dropped and the stream is terminated
+    pub struct CatchUnwind<S, H> {
+        #[pin]
+        stream: Option<S>,
+        on_panic: Option<H>,
+    }
+}
+
+impl<S, H> CatchUnwind<S, H>
+where
+    S: Stream,
+    H: FnOnce(PanicPayload) -> Option<S::Item>,
+{
+    /// Wrap an arbitrary stream
+    ///
+    /// The hook is called with the payload of the first panic.
+    /// If it returns an item, the item is emitted before the stream terminates
+    #[inline]
+    pub fn new(stream: S, on_panic: H) -> Self {
+        Self {
+            stream: Some(stream),
+            on_panic: Some(on_panic),
+        }
+    }
+}
+
+impl<S, H> CatchUnwind<S, H> {
+    /// Whether the wrapped stream panicked
+    #[inline]
+    pub fn panicked(&self) -> bool {
+        self.stream.is_none()
+    }
+}
+
+impl<S, H> fmt::Debug for CatchUnwind<S, H>
+where
+    S: fmt::Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CatchUnwind")
+            .field("stream", &self.stream)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S, H> Stream for CatchUnwind<S, H>
+where
+    S: Stream,
+    H: FnOnce(PanicPayload) -> Option<S::Item>,
+{
+    type Item = S::Item;
+
+    #[inline]
+    fn poll_next(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+        let Some(stream) = this.stream.as_mut().as_pin_mut() else {
+            return Poll::Ready(None);
+        };
+
+        match panic::catch_unwind(AssertUnwindSafe(|| stream.poll_next(cx))) {
+            Ok(poll) => poll,
+            Err(payload) => {
+                // the stream is left in whatever state it panicked in. drop it so it never gets polled again.
+                this.stream.set(None);
+                Poll::Ready(this.on_panic.take().and_then(|on_panic| on_panic(payload)))
+            }
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.stream {
+            // the hook might emit one more item
+            Some(ref stream) => {
+                let (lower, upper) = stream.size_hint();
+                (lower, upper.and_then(|upper| upper.checked_add(1)))
+            }
+            None => (0, Some(0)),
+        }
+    }
+}
+
+impl<S, H> FusedStream for CatchUnwind<S, H>
+where
+    S: FusedStream,
+    H: FnOnce(PanicPayload) -> Option<S::Item>,
+{
+    #[inline]
+    fn is_terminated(&self) -> bool {
+        self.stream.as_ref().is_none_or(S::is_terminated)
+    }
+}
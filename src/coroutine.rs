@@ -0,0 +1,89 @@
+use crate::{
+    coroutine_yielder::CoroutineYielder,
+    stream::{AsynkStrim, StreamState},
+};
+use core::{
+    future::Future,
+    marker::PhantomData,
+    pin::Pin,
+    task::{self, Poll},
+};
+use pin_project_lite::pin_project;
+
+/// Slot shared between the coroutine and its yielder
+///
+/// The yielder moves the item in, the coroutine moves the resume value in.
+pub(crate) struct CoroutineSlot<Item, Resume> {
+    pub item: Option<Item>,
+    pub resume: Option<Resume>,
+}
+
+pin_project! {
+    /// Coroutine created via [`coroutine_fn`](crate::coroutine_fn)
+    ///
+    /// Drive it via [`Coroutine::resume`]
+    #[project(!Unpin)]
+    pub struct Coroutine<F, Fut, Item, Resume> {
+        #[pin]
+        state: AsynkStrim<F, Fut, ()>,
+        resume: Option<Resume>,
+        _item: PhantomData<Item>,
+    }
+}
+
+impl<F, Fut, Item, Resume> Coroutine<F, Fut, Item, Resume>
+where
+    F: FnOnce(CoroutineYielder<Item, Resume>, Resume) -> Fut,
+    Fut: Future<Output = ()>,
+{
+    #[inline]
+    pub(crate) fn new(func: F) -> Self {
+        Self {
+            state: AsynkStrim::Initial { func },
+            resume: None,
+            _item: PhantomData,
+        }
+    }
+
+    /// Resume the coroutine with a value and wait for the next item
+    ///
+    /// The first call starts the coroutine and passes the value to the closure.
+    /// Every following call passes the value to the `yield_item` call the coroutine is suspended on.
+    ///
+    /// Returns `None` once the coroutine has finished.
+    #[inline]
+    pub async fn resume(mut self: Pin<&mut Self>, value: Resume) -> Option<Item> {
+        *self.as_mut().project().resume = Some(value);
+        core::future::poll_fn(|cx| self.as_mut().poll_resume(cx)).await
+    }
+
+    /// Check whether the coroutine has finished
+    #[inline]
+    pub fn is_finished(&self) -> bool {
+        self.state.state() == StreamState::Finished
+    }
+
+    #[inline]
+    fn poll_resume(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Option<Item>> {
+        let this = self.project();
+        let mut slot = CoroutineSlot {
+            item: None,
+            resume: this.resume.take(),
+        };
+
+        let poll_output = this.state.poll_step_with(cx, &mut slot, |func, id, slot| {
+            // we only ever get here via `resume` which always sets the value
+            let resume = slot.resume.take().expect("missing resume value");
+            func(CoroutineYielder::new(id), resume)
+        });
+
+        // the coroutine didn't get around to pick up the value. keep it for the next poll.
+        *this.resume = slot.resume;
+
+        match (poll_output, slot.item) {
+            (Poll::Ready(..), ..) => Poll::Ready(None),
+            (Poll::Pending, Some(item)) => Poll::Ready(Some(item)),
+            (Poll::Pending, None) => Poll::Pending,
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use crate::{coroutine::CoroutineSlot, waker::StreamId};
+use core::{
+    future::Future,
+    marker::PhantomData,
+    pin::Pin,
+    task::{self, Poll},
+};
+
+struct CoroutineYieldFuture<Item, Resume> {
+    item: Option<Item>,
+    stream_id: StreamId,
+    _resume: PhantomData<Resume>,
+}
+
+impl<Item, Resume> Future for CoroutineYieldFuture<Item, Resume> {
+    type Output = Resume;
+
+    #[inline]
+    fn poll(mut self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Self::Output> {
+        let frame = crate::waker::find_frame_by_id(cx, self.stream_id)
+            .expect("no matching stream frame found");
+
+        let slot = {
+            let mut slot_ptr = frame.out_ref::<CoroutineSlot<Item, Resume>>();
+
+            // the coroutine always points the frame to a valid slot
+            #[allow(unsafe_code)]
+            unsafe {
+                slot_ptr.as_mut()
+            }
+        };
+
+        if self.item.is_some() {
+            assert!(slot.item.is_none(), "double yield. slow down, bestie");
+            slot.item = self.item.take();
+            return Poll::Pending;
+        }
+
+        match slot.resume.take() {
+            Some(value) => Poll::Ready(value),
+            None => Poll::Pending,
+        }
+    }
+}
+
+impl<Item, Resume> Unpin for CoroutineYieldFuture<Item, Resume> {}
+
+/// Handle to allow you to yield something from the coroutine and receive the resume value
+pub struct CoroutineYielder<Item, Resume> {
+    _marker: PhantomData<(Item, Resume)>,
+    stream_id: StreamId,
+}
+
+impl<Item, Resume> CoroutineYielder<Item, Resume> {
+    #[inline]
+    pub(crate) fn new(stream_id: StreamId) -> Self {
+        Self {
+            _marker: PhantomData,
+            stream_id,
+        }
+    }
+
+    /// Yield an item from the coroutine
+    ///
+    /// Resolves to the value the consumer passes into the next [`Coroutine::resume`](crate::Coroutine::resume) call
+    #[inline]
+    pub async fn yield_item(&mut self, item: Item) -> Resume {
+        let future = CoroutineYieldFuture {
+            item: Some(item),
+            stream_id: self.stream_id,
+            _resume: PhantomData,
+        };
+
+        future.await
+    }
+}
@@ -0,0 +1,96 @@
+use core::ops::ControlFlow;
+
+/// Item type of a fallible stream
+///
+/// Splits an item into a success value it continues with or a residual it short-circuits with.
+/// Implemented for [`Result`], [`Option`] and [`ControlFlow`].
+pub trait Fallible: Sized {
+    /// Value the item continues with
+    type Output;
+
+    /// Value the item short-circuits with
+    type Residual;
+
+    /// Same kind of item with a different success value
+    ///
+    /// The closure of a fallible stream returns this with its output, so `?` works inside of it.
+    type WithOutput<O>: Fallible<Output = O, Residual = Self::Residual>;
+
+    /// Wrap a success value
+    fn from_output(output: Self::Output) -> Self;
+
+    /// Wrap a residual
+    fn from_residual(residual: Self::Residual) -> Self;
+
+    /// Decide whether to continue or to short-circuit
+    fn branch(self) -> ControlFlow<Self::Residual, Self::Output>;
+}
+
+impl<T, E> Fallible for Result<T, E> {
+    type Output = T;
+    type Residual = E;
+    type WithOutput<O> = Result<O, E>;
+
+    #[inline]
+    fn from_output(output: Self::Output) -> Self {
+        Ok(output)
+    }
+
+    #[inline]
+    fn from_residual(residual: Self::Residual) -> Self {
+        Err(residual)
+    }
+
+    #[inline]
+    fn branch(self) -> ControlFlow<Self::Residual, Self::Output> {
+        match self {
+            Ok(output) => ControlFlow::Continue(output),
+            Err(residual) => ControlFlow::Break(residual),
+        }
+    }
+}
+
+impl<T> Fallible for Option<T> {
+    type Output = T;
+    type Residual = ();
+    type WithOutput<O> = Option<O>;
+
+    #[inline]
+    fn from_output(output: Self::Output) -> Self {
+        Some(output)
+    }
+
+    #[inline]
+    fn from_residual((): Self::Residual) -> Self {
+        None
+    }
+
+    #[inline]
+    fn branch(self) -> ControlFlow<Self::Residual, Self::Output> {
+        match self {
+            Some(output) => ControlFlow::Continue(output),
+            None => ControlFlow::Break(()),
+        }
+    }
+}
+
+impl<B, C> Fallible for ControlFlow<B, C> {
+    type Output = C;
+    type Residual = B;
+    type WithOutput<O> = ControlFlow<B, O>;
+
+    #[inline]
+    fn from_output(output: Self::Output) -> Self {
+        ControlFlow::Continue(output)
+    }
+
+    #[inline]
+    fn from_residual(residual: Self::Residual) -> Self {
+        ControlFlow::Break(residual)
+    }
+
+    #[inline]
+    fn branch(self) -> ControlFlow<Self::Residual, Self::Output> {
+        self
+    }
+}
@@ -0,0 +1,77 @@
+use crate::{
+    stream::{StreamFn, StreamState},
+    yielder::Yielder,
+};
+use core::{
+    future::Future,
+    iter::FusedIterator,
+    pin::Pin,
+    task::{self, Poll, Waker},
+};
+use futures_core::Stream;
+use pin_project_lite::pin_project;
+
+pin_project! {
+    /// Iterator created via [`iter_fn`](crate::iter_fn)
+    ///
+    /// The generator has to be pinned before it can be iterated over.
+    /// `Pin<&mut IterFn<..>>` implements [`Iterator`].
+    #[project(!Unpin)]
+    pub struct IterFn<F, Fut, Item>
+    where
+        Fut: Future,
+    {
+        #[pin]
+        stream: StreamFn<F, Fut, Item>,
+    }
+}
+
+impl<F, Fut, Item> IterFn<F, Fut, Item>
+where
+    Fut: Future,
+{
+    #[inline]
+    pub(crate) fn new(func: F) -> Self {
+        Self {
+            stream: StreamFn::new(func),
+        }
+    }
+
+    /// Check whether the generator has finished
+    #[inline]
+    pub fn is_finished(&self) -> bool {
+        self.stream.state() == StreamState::Finished
+    }
+}
+
+impl<F, Fut, Item> Iterator for Pin<&mut IterFn<F, Fut, Item>>
+where
+    F: FnOnce(Yielder<Item>) -> Fut,
+    Fut: Future,
+{
+    type Item = Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut cx = task::Context::from_waker(Waker::noop());
+
+        match self.as_mut().project().stream.poll_next(&mut cx) {
+            Poll::Ready(item) => item,
+            Poll::Pending => {
+                panic!("iterator generator awaited something other than a yield. nothing will ever wake it up")
+            }
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.stream.size_hint()
+    }
+}
+
+impl<F, Fut, Item> FusedIterator for Pin<&mut IterFn<F, Fut, Item>>
+where
+    F: FnOnce(Yielder<Item>) -> Fut,
+    Fut: Future,
+{
+}
@@ -0,0 +1,116 @@
+use crate::{waker::StreamId, LendingStream, StreamState};
+use core::{
+    cell::Cell,
+    fmt,
+    future::Future,
+    marker::PhantomData,
+    pin::Pin,
+    ptr::{self, NonNull},
+    task::{self, Poll},
+};
+use pin_project_lite::pin_project;
+
+/// Slot shared between the lending stream and its yielder
+///
+/// The yielder points it to the cell holding the lent item.
+pub(crate) struct LendingSlot<T: ?Sized> {
+    pub lent: Option<NonNull<Cell<Option<NonNull<T>>>>>,
+}
+
+pin_project! {
+    /// Lending stream created via [`lending_stream_fn`](crate::lending_stream_fn)
+    ///
+    /// Consume it via [`LendingStream`]
+    #[project(!Unpin)]
+    pub struct LendingStreamFn<Fut, T: ?Sized> {
+        #[pin]
+        fut: Option<Fut>,
+        id: Option<StreamId>,
+        _item: PhantomData<fn(&T)>,
+    }
+}
+
+impl<Fut, T: ?Sized> LendingStreamFn<Fut, T>
+where
+    Fut: Future<Output = ()>,
+{
+    #[inline]
+    pub(crate) fn new(fut: Fut) -> Self {
+        Self {
+            fut: Some(fut),
+            id: None,
+            _item: PhantomData,
+        }
+    }
+
+    /// Current state of the stream
+    #[inline]
+    pub fn state(&self) -> StreamState {
+        match (&self.fut, self.id) {
+            (Some(..), None) => StreamState::NotStarted,
+            (Some(..), Some(..)) => StreamState::Running,
+            (None, ..) => StreamState::Finished,
+        }
+    }
+}
+
+impl<Fut, T: ?Sized> fmt::Debug for LendingStreamFn<Fut, T>
+where
+    Fut: Future<Output = ()>,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LendingStreamFn")
+            .field("state", &self.state())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<Fut, T: ?Sized> LendingStream for LendingStreamFn<Fut, T>
+where
+    Fut: Future<Output = ()>,
+{
+    type Item<'a>
+        = &'a T
+    where
+        Self: 'a;
+
+    #[inline]
+    fn poll_next(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Option<Self::Item<'_>>> {
+        let mut this = self.project();
+
+        let Some(fut) = this.fut.as_mut().as_pin_mut() else {
+            return Poll::Ready(None);
+        };
+
+        // the future may borrow from itself once it runs, so stay away from the address of the whole stream.
+        // the id field doesn't move either and is never borrowed by the future.
+        let id = match *this.id {
+            Some(id) => id,
+            None => *this
+                .id
+                .insert(StreamId::new(ptr::from_ref(&*this.id) as usize)),
+        };
+        let mut slot = LendingSlot { lent: None };
+
+        match crate::waker::with_context(cx, id, &mut slot, |cx| fut.poll(cx)) {
+            Poll::Ready(()) => {
+                this.fut.set(None);
+                Poll::Ready(None)
+            }
+            Poll::Pending => {
+                // the cell lives inside the yielder, which the suspended future keeps in place
+                #[allow(unsafe_code)]
+                let lent = slot.lent.and_then(|lent| unsafe { lent.as_ref() }.take());
+
+                match lent {
+                    // the item is owned by the future lending it, which stays suspended until we poll again.
+                    // the returned reference borrows the stream, so that can't happen while it is alive.
+                    #[allow(unsafe_code)]
+                    Some(item) => Poll::Ready(Some(unsafe { item.as_ref() })),
+                    None => Poll::Pending,
+                }
+            }
+        }
+    }
+}
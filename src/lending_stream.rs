@@ -0,0 +1,76 @@
+use core::{
+    future::Future,
+    ops::DerefMut,
+    pin::Pin,
+    task::{self, Poll},
+};
+
+/// Stream whose items borrow from the stream itself
+///
+/// Every item is only valid until the stream is polled again,
+/// so the generator can hand out views into a buffer it reuses.
+pub trait LendingStream {
+    /// Item borrowing from the stream
+    type Item<'a>
+    where
+        Self: 'a;
+
+    /// Attempt to pull out the next item, borrowing the stream until it is dropped
+    fn poll_next(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Option<Self::Item<'_>>>;
+
+    /// Wait for the next item
+    ///
+    /// The item borrows the stream, so it has to be dropped before asking for the next one.
+    #[inline]
+    fn next(&mut self) -> Next<'_, Self>
+    where
+        Self: Unpin,
+    {
+        Next { stream: Some(self) }
+    }
+}
+
+impl<P> LendingStream for Pin<P>
+where
+    P: DerefMut<Target: LendingStream> + Unpin,
+{
+    type Item<'a>
+        = <P::Target as LendingStream>::Item<'a>
+    where
+        Self: 'a;
+
+    #[inline]
+    fn poll_next(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Option<Self::Item<'_>>> {
+        self.get_mut().as_mut().poll_next(cx)
+    }
+}
+
+/// Future returned by [`LendingStream::next`]
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Next<'a, S: ?Sized> {
+    stream: Option<&'a mut S>,
+}
+
+impl<'a, S> Future for Next<'a, S>
+where
+    S: LendingStream + Unpin + ?Sized,
+{
+    type Output = Option<S::Item<'a>>;
+
+    #[inline]
+    fn poll(mut self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Self::Output> {
+        let stream: *mut S = self.stream.take().expect("`Next` polled after completion");
+
+        // the borrow handed to `poll_next` only outlives this call if it produced an item.
+        // otherwise nothing refers to it anymore and we can take the stream back.
+        #[allow(unsafe_code)]
+        match Pin::new(unsafe { &mut *stream }).poll_next(cx) {
+            Poll::Ready(item) => Poll::Ready(item),
+            Poll::Pending => {
+                self.stream = Some(unsafe { &mut *stream });
+                Poll::Pending
+            }
+        }
+    }
+}
@@ -0,0 +1,264 @@
+use crate::{lending::LendingSlot, waker::StreamId, YieldError};
+use core::{
+    borrow::Borrow,
+    cell::Cell,
+    future::Future,
+    marker::PhantomPinned,
+    pin::Pin,
+    ptr::{self, NonNull},
+    sync::atomic::{AtomicBool, Ordering},
+    task::{self, Poll},
+};
+use pin_project_lite::pin_project;
+
+struct LendFuture<'a, T: ?Sized> {
+    item: &'a T,
+    yielder: &'a LendingYielder<T>,
+    /// Flag of the buffer the item is borrowed from
+    owner: Option<&'a AtomicBool>,
+    lent: bool,
+    _pinned: PhantomPinned,
+}
+
+impl<T: ?Sized> LendFuture<'_, T> {
+    /// Whether the stream hasn't taken our item yet
+    #[inline]
+    fn is_waiting(&self) -> bool {
+        self.yielder
+            .lent
+            .get()
+            .is_some_and(|lent| ptr::eq(lent.as_ptr(), self.item))
+    }
+
+    #[inline]
+    fn finish(&mut self) {
+        self.lent = false;
+        if let Some(owner) = self.owner {
+            owner.store(false, Ordering::Relaxed);
+        }
+    }
+}
+
+impl<T: ?Sized> Future for LendFuture<'_, T> {
+    type Output = ();
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Self::Output> {
+        // we never move out of the future
+        #[allow(unsafe_code)]
+        let this = unsafe { self.get_unchecked_mut() };
+
+        if this.lent {
+            // the stream takes the item once the generator is suspended.
+            // until then, the item has to stay where it is.
+            if this.is_waiting() {
+                return Poll::Pending;
+            }
+
+            this.finish();
+            return Poll::Ready(());
+        }
+
+        let frame = crate::waker::find_frame_by_id(cx, this.yielder.stream_id)
+            .expect("no matching stream frame found");
+
+        assert!(
+            this.yielder.lent.get().is_none(),
+            "{}",
+            YieldError::DoubleYield(())
+        );
+
+        let slot = {
+            let mut slot_ptr = frame.out_ref::<LendingSlot<T>>();
+
+            // the stream always points the frame to a valid slot
+            #[allow(unsafe_code)]
+            unsafe {
+                slot_ptr.as_mut()
+            }
+        };
+
+        this.yielder.lent.set(Some(NonNull::from(this.item)));
+        slot.lent = Some(NonNull::from(&this.yielder.lent));
+        this.lent = true;
+        if let Some(owner) = this.owner {
+            owner.store(true, Ordering::Relaxed);
+        }
+
+        Poll::Pending
+    }
+}
+
+impl<T: ?Sized> Drop for LendFuture<'_, T> {
+    #[inline]
+    fn drop(&mut self) {
+        // dropped before the stream took the item. take it back so the stream never sees it.
+        if self.lent {
+            if self.is_waiting() {
+                self.yielder.lent.set(None);
+            }
+
+            self.finish();
+        }
+    }
+}
+
+/// Handle to allow you to lend items to the consumer of a lending stream
+///
+/// Created via [`lending_stream_fn`](crate::lending_stream_fn)
+pub struct LendingYielder<T: ?Sized> {
+    pub(crate) stream_id: StreamId,
+    pub(crate) lent: Cell<Option<NonNull<T>>>,
+}
+
+// the cell is only touched by the future lending through the mutably borrowed yielder,
+// and by the stream after its generator got suspended. never by two threads at once.
+#[allow(unsafe_code)]
+unsafe impl<T: ?Sized + Sync> Send for LendingYielder<T> {}
+
+// there is no way to do anything with a shared reference to the yielder
+#[allow(unsafe_code)]
+unsafe impl<T: ?Sized + Sync> Sync for LendingYielder<T> {}
+
+impl<T: ?Sized> LendingYielder<T> {
+    #[inline]
+    pub(crate) fn new(stream_id: StreamId) -> Self {
+        Self {
+            stream_id,
+            lent: Cell::new(None),
+        }
+    }
+
+    /// Lend the value to the consumer
+    ///
+    /// Suspends until the consumer asks for the next item, then hands the value back.
+    ///
+    /// The value has to be owned, so it stays valid even if the future is leaked.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the yielder is used outside of its stream
+    #[inline]
+    pub async fn lend<B>(&mut self, value: B) -> B
+    where
+        B: Borrow<T> + 'static,
+    {
+        self.lend_with(value, B::borrow).await
+    }
+
+    /// Lend a part of the value to the consumer
+    ///
+    /// Works like [`LendingYielder::lend`], but lends whatever the projection returns.
+    /// Useful to lend only the filled part of a buffer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the yielder is used outside of its stream
+    #[inline]
+    pub async fn lend_with<B, F>(&mut self, value: B, project: F) -> B
+    where
+        B: 'static,
+        F: FnOnce(&B) -> &T,
+    {
+        let future = LendFuture {
+            item: project(&value),
+            yielder: &*self,
+            owner: None,
+            lent: false,
+            _pinned: PhantomPinned,
+        };
+
+        future.await;
+        value
+    }
+
+    /// Create a buffer to lend borrows of
+    ///
+    /// Unlike [`LendingYielder::lend`], the value stays in place and only a borrow of it is lent.
+    /// Pin the buffer inside the generator and lend from it via [`LendBuffer::lend`].
+    #[inline]
+    pub fn buffer<B>(&self, value: B) -> LendBuffer<'_, B, T> {
+        LendBuffer {
+            value,
+            yielder: self,
+            lent: AtomicBool::new(false),
+            _pinned: PhantomPinned,
+        }
+    }
+}
+
+pin_project! {
+    /// Buffer lending borrows of its value to the consumer of a lending stream
+    ///
+    /// Created via [`LendingYielder::buffer`].
+    /// Since it is pinned, whatever it lent stays valid until its value is accessed mutably or it is dropped.
+    /// Both take back the lent item first, so the consumer never sees a stale borrow.
+    pub struct LendBuffer<'a, B, T: ?Sized> {
+        value: B,
+        yielder: &'a LendingYielder<T>,
+        lent: AtomicBool,
+        #[pin]
+        _pinned: PhantomPinned,
+    }
+
+    impl<B, T: ?Sized> PinnedDrop for LendBuffer<'_, B, T> {
+        fn drop(this: Pin<&mut Self>) {
+            this.withdraw();
+        }
+    }
+}
+
+impl<B, T: ?Sized> LendBuffer<'_, B, T> {
+    /// Take back the item a leaked lend left in the yielder
+    ///
+    /// Futures lending from the buffer borrow it, so there is nothing to take back unless one of them got leaked.
+    #[inline]
+    fn withdraw(self: Pin<&mut Self>) {
+        let this = self.project();
+        if this.lent.swap(false, Ordering::Relaxed) {
+            this.yielder.lent.set(None);
+        }
+    }
+
+    /// Shared access to the value
+    #[inline]
+    #[must_use]
+    pub fn value(self: Pin<&Self>) -> &B {
+        &self.get_ref().value
+    }
+
+    /// Mutable access to the value
+    ///
+    /// Takes back whatever the buffer lent, since the borrow is about to be invalidated
+    #[inline]
+    #[must_use]
+    pub fn value_mut(mut self: Pin<&mut Self>) -> &mut B {
+        self.as_mut().withdraw();
+        self.project().value
+    }
+
+    /// Lend a part of the value to the consumer
+    ///
+    /// Suspends until the consumer asks for the next item.
+    /// Lends whatever the projection returns, e.g. only the filled part of the buffer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the yielder is used outside of its stream
+    #[inline]
+    pub async fn lend<F>(self: Pin<&Self>, project: F)
+    where
+        F: FnOnce(&B) -> &T,
+    {
+        let this = self.get_ref();
+        let future = LendFuture {
+            item: project(&this.value),
+            yielder: this.yielder,
+            owner: Some(&this.lent),
+            lent: false,
+            _pinned: PhantomPinned,
+        };
+
+        future.await;
+    }
+}
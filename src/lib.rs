@@ -1,20 +1,60 @@
 #![no_std]
+#![cfg_attr(feature = "backend-context-ext", feature(context_ext, local_waker))]
 #![doc = include_str!("../README.md")]
 #![forbid(rust_2018_idioms)]
 #![deny(missing_docs, unsafe_code)]
 #![warn(clippy::all, clippy::pedantic)]
 #![allow(forbidden_lint_groups)]
 
+#[cfg(feature = "std")]
+extern crate std;
+
 use core::{future::Future, pin::pin, task};
-use futures_core::Stream;
 
+mod buffered;
+mod buffered_yielder;
+#[cfg(feature = "std")]
+mod catch_unwind;
+mod coroutine;
+mod coroutine_yielder;
+mod fallible;
+mod iter;
+mod lending;
+mod lending_stream;
+mod lending_yielder;
+mod local_yielder;
+mod mapped_try_yielder;
+mod mapped_yielder;
+mod sink;
+mod sink_receiver;
 mod stream;
 mod try_yielder;
 mod waker;
+mod yield_error;
+mod yield_trait;
 mod yielder;
 
+pub use self::buffered::{BufferedStreamFn, Chunk};
+pub use self::buffered_yielder::BufferedYielder;
+#[cfg(feature = "std")]
+pub use self::catch_unwind::{CatchUnwind, PanicPayload};
+pub use self::coroutine::Coroutine;
+pub use self::coroutine_yielder::CoroutineYielder;
+pub use self::fallible::Fallible;
+pub use self::iter::IterFn;
+pub use self::lending::LendingStreamFn;
+pub use self::lending_stream::{LendingStream, Next};
+pub use self::lending_yielder::{LendBuffer, LendingYielder};
+pub use self::local_yielder::{LocalTryYielder, LocalYielder};
+pub use self::mapped_try_yielder::MappedTryYielder;
+pub use self::mapped_yielder::MappedYielder;
+pub use self::sink::SinkFn;
+pub use self::sink_receiver::SinkReceiver;
+pub use self::stream::{FallibleStreamFn, Started, StreamFn, StreamState, TryStreamFn};
 pub use self::try_yielder::TryYielder;
-pub use self::yielder::Yielder;
+pub use self::yield_error::YieldError;
+pub use self::yield_trait::{DynYield, Yield};
+pub use self::yielder::{UnwrappedYielder, Yielder};
 
 /// Unwrap the waker
 ///
@@ -23,6 +63,12 @@ pub use self::yielder::Yielder;
 ///
 /// An example is the [`embassy`](https://embassy.dev/) crate.
 ///
+/// With the `backend-thread-local` and `backend-context-ext` features the waker isn't wrapped,
+/// so this function just passes the waker through.
+///
+/// Futures that cache their waker and check it via [`Waker::will_wake`](core::task::Waker::will_wake) should be polled in here as well,
+/// since the wrapper never compares equal across polls.
+///
 /// # Panics
 ///
 /// The future will panic if the waker is not found.
@@ -34,13 +80,67 @@ where
 {
     let mut future = pin!(future);
     core::future::poll_fn(|cx| {
-        let unwrapped = crate::waker::unwrap_inner(cx.waker()).expect("waker not found");
+        let unwrapped = crate::waker::unwrap_inner(cx).expect("waker not found");
         let mut cx = task::Context::from_waker(unwrapped);
         future.as_mut().poll(&mut cx)
     })
     .await
 }
 
+/// Unwrap the waker while still being able to yield
+///
+/// Works like [`unwrap_waker`], but hands the closure an [`UnwrappedYielder`].
+/// Whenever it yields, the frame chain of the stream gets re-installed for it,
+/// so you can interleave calls into foreign code with yields, even if that code wraps the waker again.
+///
+/// The handle only lives as long as the call of the closure, so it can't be leaked or sent to another thread.
+///
+/// # Example
+///
+/// ```
+/// # use futures_lite::StreamExt;
+/// # use std::pin::pin;
+/// # futures_lite::future::block_on(async {
+/// let stream = asynk_strim::stream_fn(|mut yielder| async move {
+///     asynk_strim::unwrap_waker_yielding(&mut yielder, async |mut yielder| {
+///         for i in 0..3 {
+///             // call into code that needs the original waker here
+///             yielder.yield_item(i).await;
+///         }
+///     })
+///     .await;
+/// });
+///
+/// let mut stream = pin!(stream);
+/// assert_eq!(stream.next().await, Some(0));
+/// assert_eq!(stream.next().await, Some(1));
+/// assert_eq!(stream.next().await, Some(2));
+/// assert_eq!(stream.next().await, None);
+/// # });
+/// ```
+///
+/// # Panics
+///
+/// The future will panic if the waker is not found.
+/// This happens if you use this function outside of the context of a stream generator.
+#[inline]
+pub async fn unwrap_waker_yielding<Item, F, Output>(yielder: &mut Yielder<Item>, func: F) -> Output
+where
+    F: AsyncFnOnce(UnwrappedYielder<'_, Item>) -> Output,
+{
+    let yielder: &Yielder<Item> = yielder;
+    let mut future = pin!(func(UnwrappedYielder::new(yielder)));
+    core::future::poll_fn(|cx| {
+        let frame = crate::waker::find_frame(cx).expect("waker not found");
+        let unwrapped = crate::waker::unwrap_inner(cx).expect("waker not found");
+        yielder.stash.lend(&frame, || {
+            let mut cx = task::Context::from_waker(unwrapped);
+            future.as_mut().poll(&mut cx)
+        })
+    })
+    .await
+}
+
 /// Create a new stream
 ///
 /// # Example
@@ -68,19 +168,19 @@ where
 /// }
 /// # });
 #[inline]
-pub fn stream_fn<F, Item, Fut>(func: F) -> impl Stream<Item = Item>
+pub fn stream_fn<F, Item, Fut>(func: F) -> StreamFn<F, Fut, Item>
 where
     F: FnOnce(Yielder<Item>) -> Fut,
     Fut: Future<Output = ()>,
 {
-    crate::stream::init(func)
+    StreamFn::new(func)
 }
 
 /// Jokey alias for [`stream_fn`]
 ///
 /// For more elaborate documentation, see [`stream_fn`]
 #[inline]
-pub fn strim_fn<F, Item, Fut>(func: F) -> impl Stream<Item = Item>
+pub fn strim_fn<F, Item, Fut>(func: F) -> StreamFn<F, Fut, Item>
 where
     F: FnOnce(Yielder<Item>) -> Fut,
     Fut: Future<Output = ()>,
@@ -116,28 +216,563 @@ where
 /// # });
 /// ```
 #[inline]
-pub fn try_stream_fn<F, Ok, Error, Fut>(func: F) -> impl Stream<Item = Result<Ok, Error>>
+pub fn try_stream_fn<F, Ok, Error, Fut>(func: F) -> TryStreamFn<F, Fut, Ok, Error>
 where
     F: FnOnce(TryYielder<Ok, Error>) -> Fut,
     Fut: Future<Output = Result<(), Error>>,
 {
-    crate::stream::init(|mut yielder: TryYielder<_, _>| async move {
-        // trivially copyable. bit-wise copy is fine.
-        #[allow(unsafe_code)]
-        if let Err(err) = func(unsafe { core::ptr::read(&yielder) }).await {
-            yielder.yield_error(err).await;
-        }
-    })
+    TryStreamFn::new(func)
 }
 
 /// Jokey alias for [`try_stream_fn`]
 ///
 /// For more elaborate documentation, see [`try_stream_fn`]
 #[inline]
-pub fn try_strim_fn<F, Ok, Error, Fut>(func: F) -> impl Stream<Item = Result<Ok, Error>>
+pub fn try_strim_fn<F, Ok, Error, Fut>(func: F) -> TryStreamFn<F, Fut, Ok, Error>
 where
     F: FnOnce(TryYielder<Ok, Error>) -> Fut,
     Fut: Future<Output = Result<(), Error>>,
 {
     try_stream_fn(func)
 }
+
+/// Create a new try stream which terminates with the first error
+///
+/// Works like [`try_stream_fn`], but the first error reaching the consumer is the last item.
+/// The future of the closure is dropped right away and the stream reports itself as terminated.
+///
+/// # Example
+///
+/// ```
+/// # use futures_core::FusedStream;
+/// # use futures_lite::StreamExt;
+/// # use std::pin::pin;
+/// # futures_lite::future::block_on(async {
+/// let stream = asynk_strim::fail_fast_try_stream_fn(|mut yielder| async move {
+///     yielder.yield_ok(1).await;
+///     yielder.yield_error("oh no").await;
+///     yielder.yield_ok(2).await;
+///
+///     Ok(())
+/// });
+///
+/// let mut stream = pin!(stream);
+/// assert_eq!(stream.next().await, Some(Ok(1)));
+/// assert_eq!(stream.next().await, Some(Err("oh no")));
+/// assert!(stream.is_terminated());
+/// assert_eq!(stream.next().await, None);
+/// # });
+/// ```
+#[inline]
+pub fn fail_fast_try_stream_fn<F, Ok, Error, Fut>(func: F) -> TryStreamFn<F, Fut, Ok, Error>
+where
+    F: FnOnce(TryYielder<Ok, Error>) -> Fut,
+    Fut: Future<Output = Result<(), Error>>,
+{
+    TryStreamFn::new_fail_fast(func)
+}
+
+/// Create a new fallible stream
+///
+/// Generalises [`try_stream_fn`] to every item implementing [`Fallible`], such as [`Option`] or [`ControlFlow`](core::ops::ControlFlow).
+/// The closure returns the same kind of item, so you can short-circuit with `?`.
+/// The residual is emitted as the final item.
+///
+/// # Example
+///
+/// ```
+/// # use futures_lite::stream;
+/// # use std::pin::pin;
+/// let readings = [Some(21), Some(23), None, Some(22)];
+///
+/// let stream = pin!(asynk_strim::fallible_stream_fn(|mut yielder| async move {
+///     for reading in readings {
+///         let reading = reading?;
+///         yielder.yield_item(Some(reading * 10)).await;
+///     }
+///
+///     Some(())
+/// }));
+///
+/// let items: Vec<_> = stream::block_on(stream).collect();
+/// assert_eq!(items, [Some(210), Some(230), None]);
+/// ```
+#[inline]
+pub fn fallible_stream_fn<F, Item, Fut>(func: F) -> FallibleStreamFn<F, Fut, Item>
+where
+    F: FnOnce(Yielder<Item>) -> Fut,
+    Fut: Future<Output = Item::WithOutput<()>>,
+    Item: Fallible,
+{
+    FallibleStreamFn::new(func)
+}
+
+/// Create a new fallible stream whose closure returns a final value
+///
+/// Once the stream has returned `None`, the value can be retrieved via [`FallibleStreamFn::take_output`].
+/// See [`fallible_stream_fn`] for the details.
+#[inline]
+pub fn fallible_stream_fn_with_output<F, Item, Output, Fut>(
+    func: F,
+) -> FallibleStreamFn<F, Fut, Item, Output>
+where
+    F: FnOnce(Yielder<Item>) -> Fut,
+    Fut: Future<Output = Item::WithOutput<Output>>,
+    Item: Fallible,
+{
+    FallibleStreamFn::new(func)
+}
+
+/// Create a new stream from an async closure borrowing the yielder
+///
+/// Works like [`stream_fn`], but the yielder stays inside the state of the stream and the closure only borrows it.
+/// The closure doesn't have to be `move` and can lend the yielder to helpers taking `&mut Yielder`.
+///
+/// # Example
+///
+/// ```
+/// # use futures_lite::stream;
+/// # use std::pin::pin;
+/// use asynk_strim::Yielder;
+///
+/// async fn twice(yielder: &mut Yielder<u32>, item: u32) {
+///     yielder.yield_item(item).await;
+///     yielder.yield_item(item).await;
+/// }
+///
+/// let items = vec![1, 2];
+/// let stream = pin!(asynk_strim::async_stream_fn(async |yielder: &mut Yielder<u32>| {
+///     for item in &items {
+///         twice(yielder, *item).await;
+///     }
+/// }));
+///
+/// assert_eq!(stream::block_on(stream).collect::<Vec<_>>(), [1, 1, 2, 2]);
+/// ```
+#[inline]
+pub fn async_stream_fn<F, Item>(func: F) -> StreamFn<Started, impl Future<Output = ()>, Item>
+where
+    F: AsyncFnOnce(&mut Yielder<Item>),
+{
+    StreamFn::started(async move {
+        let stream_id = waker::current_stream_id().await;
+        let mut yielder = Yielder::new(stream_id);
+        func(&mut yielder).await;
+    })
+}
+
+/// Create a new try stream from an async closure borrowing the yielder
+///
+/// Works like [`try_stream_fn`], but the closure only borrows the yielder.
+/// See [`async_stream_fn`] for the details.
+#[inline]
+pub fn async_try_stream_fn<F, Ok, Error>(
+    func: F,
+) -> TryStreamFn<Started, impl Future<Output = Result<(), Error>>, Ok, Error>
+where
+    F: AsyncFnOnce(&mut TryYielder<Ok, Error>) -> Result<(), Error>,
+{
+    TryStreamFn::started(async move {
+        let stream_id = waker::current_stream_id().await;
+        let mut yielder = TryYielder::from(Yielder::new(stream_id));
+        func(&mut yielder).await
+    })
+}
+
+/// Create a new lending stream
+///
+/// The items are borrowed from values owned by the generator, so it can reuse a single buffer for every item.
+/// The closure lends borrows of a pinned [`LendBuffer`], which stays in place while the consumer holds the item.
+/// Values can also be moved in and out via [`LendingYielder::lend`].
+/// Consume the stream via [`LendingStream`].
+///
+/// # Example
+///
+/// ```
+/// # use std::pin::pin;
+/// use asynk_strim::{LendingStream, LendingYielder};
+///
+/// # futures_lite::future::block_on(async {
+/// let stream = asynk_strim::lending_stream_fn(async |yielder: &mut LendingYielder<[u8]>| {
+///     let mut buffer = pin!(yielder.buffer([0; 4096]));
+///     for line in ["moin", "servus"] {
+///         let n = line.len();
+///         buffer.as_mut().value_mut()[..n].copy_from_slice(line.as_bytes());
+///         buffer.as_ref().lend(|buf| &buf[..n]).await;
+///     }
+/// });
+///
+/// let mut stream = pin!(stream);
+/// assert_eq!(stream.next().await, Some(&b"moin"[..]));
+/// assert_eq!(stream.next().await, Some(&b"servus"[..]));
+/// assert_eq!(stream.next().await, None);
+/// # });
+/// ```
+#[inline]
+pub fn lending_stream_fn<F, T>(func: F) -> LendingStreamFn<impl Future<Output = ()>, T>
+where
+    F: AsyncFnOnce(&mut LendingYielder<T>),
+    T: ?Sized,
+{
+    LendingStreamFn::new(async move {
+        let stream_id = waker::current_stream_id().await;
+        let mut yielder = LendingYielder::new(stream_id);
+        func(&mut yielder).await;
+    })
+}
+
+/// Create a new stream whose yielder is confined to the stream
+///
+/// Works like [`stream_fn`], but the stream owns the [`LocalYielder`] and the closure only borrows it.
+/// Every stream brands its yielder with a lifetime of its own,
+/// so leaking the yielder or using it with another stream is a compile error instead of a panic.
+/// The yielder can't be sent to other threads either.
+///
+/// Since the stream holds the yielder while it is suspended, the stream isn't `Send`.
+/// Use [`async_stream_fn`] if it has to move between threads.
+///
+/// # Example
+///
+/// ```
+/// # use futures_lite::StreamExt;
+/// # use std::pin::pin;
+/// use asynk_strim::LocalYielder;
+///
+/// # futures_lite::future::block_on(async {
+/// let stream = asynk_strim::local_stream_fn(async |yielder: &mut LocalYielder<'_, _>| {
+///     yielder.yield_item("stays right here").await;
+/// });
+///
+/// let mut stream = pin!(stream);
+/// assert_eq!(stream.next().await, Some("stays right here"));
+/// assert_eq!(stream.next().await, None);
+/// # });
+/// ```
+#[inline]
+pub fn local_stream_fn<F, Item>(func: F) -> StreamFn<Started, impl Future<Output = ()>, Item>
+where
+    F: AsyncFnOnce(&mut LocalYielder<'_, Item>),
+{
+    StreamFn::started(async move {
+        let stream_id = waker::current_stream_id().await;
+        let mut yielder = LocalYielder::new(stream_id);
+        func(&mut yielder).await;
+    })
+}
+
+/// Create a new try stream whose yielder is confined to the stream
+///
+/// Works like [`try_stream_fn`], but the stream owns the [`LocalTryYielder`] and the closure only borrows it.
+/// See [`local_stream_fn`] for the details.
+#[inline]
+pub fn local_try_stream_fn<F, Ok, Error>(
+    func: F,
+) -> TryStreamFn<Started, impl Future<Output = Result<(), Error>>, Ok, Error>
+where
+    F: AsyncFnOnce(&mut LocalTryYielder<'_, Ok, Error>) -> Result<(), Error>,
+{
+    TryStreamFn::started(async move {
+        let stream_id = waker::current_stream_id().await;
+        let mut yielder = LocalTryYielder::new(stream_id);
+        func(&mut yielder).await
+    })
+}
+
+/// Create a new stream which survives a panicking closure
+///
+/// If the closure or its future panics, the panic is caught and its payload is handed to `on_panic`.
+/// The item returned from the hook is emitted as the final item, afterwards the stream terminates.
+///
+/// # Example
+///
+/// ```
+/// # use futures_lite::StreamExt;
+/// # use std::pin::pin;
+/// # futures_lite::future::block_on(async {
+/// let stream = asynk_strim::catch_unwind_stream_fn(
+///     |mut yielder| async move {
+///         yielder.yield_item("before").await;
+///         panic!("whoops");
+///     },
+///     |_payload| Some("caught"),
+/// );
+///
+/// let mut stream = pin!(stream);
+/// assert_eq!(stream.next().await, Some("before"));
+/// assert_eq!(stream.next().await, Some("caught"));
+/// assert_eq!(stream.next().await, None);
+/// # });
+/// ```
+#[cfg(feature = "std")]
+#[inline]
+pub fn catch_unwind_stream_fn<F, Item, Fut, H>(
+    func: F,
+    on_panic: H,
+) -> CatchUnwind<StreamFn<F, Fut, Item>, H>
+where
+    F: FnOnce(Yielder<Item>) -> Fut,
+    Fut: Future<Output = ()>,
+    H: FnOnce(PanicPayload) -> Option<Item>,
+{
+    CatchUnwind::new(StreamFn::new(func), on_panic)
+}
+
+/// Create a new try stream which survives a panicking closure
+///
+/// If the closure or its future panics, the payload is converted into the error type and emitted as the final item.
+/// Afterwards the stream terminates.
+///
+/// Use [`PanicPayload`] as the error type to get the raw payload.
+///
+/// # Example
+///
+/// ```
+/// # use futures_lite::StreamExt;
+/// # use std::pin::pin;
+/// use asynk_strim::{PanicPayload, TryYielder};
+///
+/// # futures_lite::future::block_on(async {
+/// let stream = asynk_strim::catch_unwind_try_stream_fn(|mut yielder: TryYielder<_, PanicPayload>| async move {
+///     yielder.yield_ok(1).await;
+///     panic!("whoops");
+/// });
+///
+/// let mut stream = pin!(stream);
+/// assert_eq!(stream.next().await.unwrap().unwrap(), 1);
+///
+/// let payload = stream.next().await.unwrap().unwrap_err();
+/// assert_eq!(payload.downcast_ref::<&str>(), Some(&"whoops"));
+/// assert!(stream.next().await.is_none());
+/// # });
+/// ```
+#[cfg(feature = "std")]
+#[inline]
+#[allow(clippy::type_complexity)]
+pub fn catch_unwind_try_stream_fn<F, Ok, Error, Fut>(
+    func: F,
+) -> CatchUnwind<TryStreamFn<F, Fut, Ok, Error>, fn(PanicPayload) -> Option<Result<Ok, Error>>>
+where
+    F: FnOnce(TryYielder<Ok, Error>) -> Fut,
+    Fut: Future<Output = Result<(), Error>>,
+    Error: From<PanicPayload>,
+{
+    CatchUnwind::new(TryStreamFn::new(func), |payload| {
+        Some(Err(Error::from(payload)))
+    })
+}
+
+/// Create a new stream whose closure returns a final value
+///
+/// Once the stream has returned `None`, the value can be retrieved via [`StreamFn::take_output`].
+///
+/// # Example
+///
+/// ```
+/// # use futures_lite::StreamExt;
+/// # use std::pin::pin;
+/// # futures_lite::future::block_on(async {
+/// let stream = asynk_strim::stream_fn_with_output(|mut yielder| async move {
+///     let mut total = 0;
+///     for item in [1, 2, 3] {
+///         total += item;
+///         yielder.yield_item(item).await;
+///     }
+///
+///     total
+/// });
+///
+/// let mut stream = pin!(stream);
+/// while let Some(item) = stream.next().await {
+///     println!("{item}");
+/// }
+///
+/// assert_eq!(stream.as_mut().take_output(), Some(6));
+/// assert_eq!(stream.as_mut().take_output(), None);
+/// # });
+/// ```
+#[inline]
+pub fn stream_fn_with_output<F, Item, Fut>(func: F) -> StreamFn<F, Fut, Item>
+where
+    F: FnOnce(Yielder<Item>) -> Fut,
+    Fut: Future,
+{
+    StreamFn::new(func)
+}
+
+/// Create a new try stream whose closure returns a final value
+///
+/// Once the stream has returned `None`, the value can be retrieved via [`TryStreamFn::take_output`].
+/// If the closure returns an error, the error is emitted as the final item and there is no output.
+///
+/// # Example
+///
+/// ```
+/// # use futures_lite::StreamExt;
+/// # use std::pin::pin;
+/// # futures_lite::future::block_on(async {
+/// let stream = asynk_strim::try_stream_fn_with_output(|mut yielder| async move {
+///     yielder.yield_ok("first page").await;
+///     yielder.yield_ok("second page").await;
+///
+///     Ok::<_, std::io::Error>("next cursor")
+/// });
+///
+/// let mut stream = pin!(stream);
+/// while let Some(item) = stream.next().await {
+///     println!("{}", item.unwrap());
+/// }
+///
+/// assert_eq!(stream.as_mut().take_output(), Some("next cursor"));
+/// # });
+/// ```
+#[inline]
+pub fn try_stream_fn_with_output<F, Ok, Error, Output, Fut>(
+    func: F,
+) -> TryStreamFn<F, Fut, Ok, Error, Output>
+where
+    F: FnOnce(TryYielder<Ok, Error>) -> Fut,
+    Fut: Future<Output = Result<Output, Error>>,
+{
+    TryStreamFn::new(func)
+}
+
+/// Create a new coroutine
+///
+/// In contrast to a stream, the consumer passes a value back into the coroutine every time it resumes it.
+/// The first value is passed to the closure, every following value is returned from the `yield_item` call the coroutine is suspended on.
+///
+/// # Example
+///
+/// ```
+/// # use std::pin::pin;
+/// # futures_lite::future::block_on(async {
+/// let coroutine = asynk_strim::coroutine_fn(|mut yielder, mut name: &str| async move {
+///     for greeting in ["Hello", "Moin", "Servus"] {
+///         name = yielder.yield_item(format!("{greeting}, {name}!")).await;
+///     }
+/// });
+///
+/// let mut coroutine = pin!(coroutine);
+/// assert_eq!(coroutine.as_mut().resume("Alice").await.as_deref(), Some("Hello, Alice!"));
+/// assert_eq!(coroutine.as_mut().resume("Bob").await.as_deref(), Some("Moin, Bob!"));
+/// assert_eq!(coroutine.as_mut().resume("Eve").await.as_deref(), Some("Servus, Eve!"));
+/// assert_eq!(coroutine.as_mut().resume("Mallory").await, None);
+/// # });
+/// ```
+#[inline]
+pub fn coroutine_fn<F, Item, Resume, Fut>(func: F) -> Coroutine<F, Fut, Item, Resume>
+where
+    F: FnOnce(CoroutineYielder<Item, Resume>, Resume) -> Fut,
+    Fut: Future<Output = ()>,
+{
+    Coroutine::new(func)
+}
+
+/// Create a new sink
+///
+/// The closure receives the items sent into the sink through the [`SinkReceiver`].
+/// Flushing drives the closure until it waits for the next item, closing drives it until it returns.
+///
+/// Errors returned from the closure are surfaced by the next call to `poll_ready`, `poll_flush` or `poll_close`.
+///
+/// # Example
+///
+/// ```
+/// # use futures_util::SinkExt;
+/// # use std::pin::pin;
+/// # use std::convert::Infallible;
+/// # futures_lite::future::block_on(async {
+/// let mut lines = Vec::new();
+/// {
+///     let lines = &mut lines;
+///     let mut sink = pin!(asynk_strim::sink_fn(|mut receiver| async move {
+///         while let Some(line) = receiver.next().await {
+///             lines.push(format!("> {line}"));
+///         }
+///
+///         Ok::<_, Infallible>(())
+///     }));
+///
+///     sink.send("Alles nur geklaut").await.unwrap();
+///     sink.send("Alles gar nicht meine").await.unwrap();
+///     sink.close().await.unwrap();
+/// }
+///
+/// assert_eq!(lines, ["> Alles nur geklaut", "> Alles gar nicht meine"]);
+/// # });
+/// ```
+#[inline]
+pub fn sink_fn<F, Item, Error, Fut>(func: F) -> SinkFn<F, Fut, Item>
+where
+    F: FnOnce(SinkReceiver<Item>) -> Fut,
+    Fut: Future<Output = Result<(), Error>>,
+{
+    SinkFn::new(func)
+}
+
+/// Create a new iterator
+///
+/// The generator is driven synchronously, so the closure must not await anything besides the yielder.
+///
+/// # Panics
+///
+/// Iterating panics if the closure awaits something that isn't a yield, since nothing would ever wake it up.
+///
+/// # Example
+///
+/// ```
+/// # use std::pin::pin;
+/// let iter = pin!(asynk_strim::iter_fn(|mut yielder| async move {
+///     let (mut a, mut b) = (0, 1);
+///     loop {
+///         yielder.yield_item(a).await;
+///         (a, b) = (b, a + b);
+///     }
+/// }));
+///
+/// let fibonacci: Vec<u32> = iter.take(8).collect();
+/// assert_eq!(fibonacci, [0, 1, 1, 2, 3, 5, 8, 13]);
+/// ```
+#[inline]
+pub fn iter_fn<F, Item, Fut>(func: F) -> IterFn<F, Fut, Item>
+where
+    F: FnOnce(Yielder<Item>) -> Fut,
+    Fut: Future<Output = ()>,
+{
+    IterFn::new(func)
+}
+
+/// Create a new buffered stream
+///
+/// The yielder pushes up to `N` items into an inline buffer without suspending the closure.
+/// The closure is only suspended once the buffer is full or it awaits something else.
+///
+/// Use [`BufferedStreamFn::poll_next_chunk`] to take all buffered items at once.
+///
+/// The [`BufferedYielder`] can be cloned to yield from several futures running concurrently inside the closure.
+/// If the buffer is full, the yields wait until the consumer drained it.
+///
+/// # Example
+///
+/// ```
+/// # use futures_lite::stream;
+/// # use std::pin::pin;
+/// let stream = pin!(asynk_strim::buffered_stream_fn::<4, _, _, _>(|yielder| async move {
+///     for i in 0..10 {
+///         yielder.yield_item(i).await;
+///     }
+/// }));
+///
+/// let items: Vec<_> = stream::block_on(stream).collect();
+/// assert_eq!(items, (0..10).collect::<Vec<_>>());
+/// ```
+#[inline]
+pub fn buffered_stream_fn<const N: usize, F, Item, Fut>(
+    func: F,
+) -> BufferedStreamFn<F, Fut, Item, N>
+where
+    F: FnOnce(BufferedYielder<Item, N>) -> Fut,
+    Fut: Future<Output = ()>,
+{
+    BufferedStreamFn::new(func)
+}
@@ -0,0 +1,230 @@
+use crate::{try_yielder::TryYielder, waker::StreamId, yielder::Yielder, DynYield, YieldError};
+use core::{
+    marker::PhantomData,
+    task::{self, Poll},
+};
+use futures_core::Stream;
+
+/// Invariant lifetime tying a yielder to the stream which created it
+///
+/// Every stream brands its yielder with a lifetime of its own,
+/// so two yielders of different streams never have the same type.
+type Brand<'brand> = PhantomData<fn(&'brand ()) -> &'brand ()>;
+
+/// Marker that keeps the yielders from being sent to or shared with other threads
+///
+/// An empty array instead of `PhantomData`, so errors about it don't point into `core`.
+type NotSend = [*const (); 0];
+
+/// Handle to allow you to yield something from the stream
+///
+/// Unlike [`Yielder`], this handle is owned by the stream and the closure only borrows it.
+/// Moving it out of the stream, swapping it with the yielder of another stream
+/// or using it on another thread is a compile error instead of a panic.
+pub struct LocalYielder<'brand, Item> {
+    yielder: Yielder<Item>,
+    _brand: Brand<'brand>,
+    _not_send: NotSend,
+}
+
+impl<Item> LocalYielder<'_, Item> {
+    #[inline]
+    pub(crate) fn new(stream_id: StreamId) -> Self {
+        Self {
+            yielder: Yielder::new(stream_id),
+            _brand: PhantomData,
+            _not_send: [],
+        }
+    }
+
+    /// Yield an item from the stream
+    ///
+    /// # Panics
+    ///
+    /// See [`Yielder::yield_item`]
+    #[inline]
+    pub async fn yield_item(&mut self, item: Item) {
+        self.yielder.yield_item(item).await;
+    }
+
+    /// Yield an item from the stream without panicking
+    ///
+    /// # Errors
+    ///
+    /// See [`Yielder::try_yield_item`]
+    #[inline]
+    pub async fn try_yield_item(&mut self, item: Item) -> Result<(), YieldError<Item>> {
+        self.yielder.try_yield_item(item).await
+    }
+
+    /// Take the item the consumer handed back
+    ///
+    /// See [`Yielder::reuse`]
+    #[inline]
+    pub async fn reuse(&mut self) -> Option<Item> {
+        self.yielder.reuse().await
+    }
+
+    /// Publish how many items the stream is going to yield from now on
+    ///
+    /// See [`Yielder::set_size_hint`]
+    #[inline]
+    pub async fn set_size_hint(&mut self, lower: usize, upper: Option<usize>) {
+        self.yielder.set_size_hint(lower, upper).await;
+    }
+
+    /// Yield every item of the stream
+    ///
+    /// Resolves once the stream is exhausted
+    #[inline]
+    pub async fn yield_from<S>(&mut self, stream: S)
+    where
+        S: Stream<Item = Item>,
+    {
+        self.yielder.yield_from(stream).await;
+    }
+
+    /// Yield every item of the iterator
+    ///
+    /// Resolves once the iterator is exhausted
+    #[inline]
+    pub async fn yield_from_iter<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = Item>,
+    {
+        self.yielder.yield_from_iter(iter).await;
+    }
+}
+
+impl<Item> DynYield<Item> for LocalYielder<'_, Item> {
+    #[inline]
+    fn poll_yield(&mut self, cx: &mut task::Context<'_>, item: &mut Option<Item>) -> Poll<()> {
+        self.yielder.poll_yield(cx, item)
+    }
+}
+
+/// Handle to allow you to yield something from the try stream
+///
+/// Unlike [`TryYielder`], this handle is owned by the stream and the closure only borrows it.
+/// See [`LocalYielder`] for the details.
+pub struct LocalTryYielder<'brand, Ok, Error> {
+    yielder: TryYielder<Ok, Error>,
+    _brand: Brand<'brand>,
+    _not_send: NotSend,
+}
+
+impl<Ok, Error> LocalTryYielder<'_, Ok, Error> {
+    #[inline]
+    pub(crate) fn new(stream_id: StreamId) -> Self {
+        Self {
+            yielder: TryYielder::from(Yielder::new(stream_id)),
+            _brand: PhantomData,
+            _not_send: [],
+        }
+    }
+
+    /// Yield a success value from the stream
+    #[inline]
+    pub async fn yield_ok(&mut self, item: Ok) {
+        self.yielder.yield_ok(item).await;
+    }
+
+    /// Yield an error value from the stream
+    #[inline]
+    pub async fn yield_error(&mut self, item: Error) {
+        self.yielder.yield_error(item).await;
+    }
+
+    /// Yield a result from the stream
+    #[inline]
+    pub async fn yield_result(&mut self, result: Result<Ok, Error>) {
+        self.yielder.yield_result(result).await;
+    }
+
+    /// Convert the value into the error type and yield it from the stream
+    #[inline]
+    pub async fn yield_error_from<E>(&mut self, error: E)
+    where
+        E: Into<Error>,
+    {
+        self.yielder.yield_error_from(error).await;
+    }
+
+    /// Yield the error and keep running
+    ///
+    /// See [`TryYielder::check`]
+    #[inline]
+    pub async fn check<T>(&mut self, result: Result<T, Error>) -> Option<T> {
+        self.yielder.check(result).await
+    }
+
+    /// Yield the success value or hand back the error
+    ///
+    /// # Errors
+    ///
+    /// See [`TryYielder::try_yield`]
+    #[inline]
+    pub async fn try_yield(&mut self, result: Result<Ok, Error>) -> Result<(), Error> {
+        self.yielder.try_yield(result).await
+    }
+
+    /// Yield a success value from the stream without panicking
+    ///
+    /// # Errors
+    ///
+    /// See [`Yielder::try_yield_item`]
+    #[inline]
+    pub async fn try_yield_ok(&mut self, item: Ok) -> Result<(), YieldError<Ok>> {
+        self.yielder.try_yield_ok(item).await
+    }
+
+    /// Yield an error value from the stream without panicking
+    ///
+    /// # Errors
+    ///
+    /// See [`Yielder::try_yield_item`]
+    #[inline]
+    pub async fn try_yield_error(&mut self, item: Error) -> Result<(), YieldError<Error>> {
+        self.yielder.try_yield_error(item).await
+    }
+
+    /// Take the success value the consumer handed back
+    ///
+    /// See [`TryYielder::reuse`]
+    #[inline]
+    pub async fn reuse(&mut self) -> Option<Ok> {
+        self.yielder.reuse().await
+    }
+
+    /// Publish how many items the stream is going to yield from now on
+    ///
+    /// See [`Yielder::set_size_hint`]
+    #[inline]
+    pub async fn set_size_hint(&mut self, lower: usize, upper: Option<usize>) {
+        self.yielder.set_size_hint(lower, upper).await;
+    }
+
+    /// Yield every success value of the stream
+    ///
+    /// # Errors
+    ///
+    /// Stops at the first error and returns it without yielding it, so you can propagate it with `?`
+    #[inline]
+    pub async fn try_yield_from<S>(&mut self, stream: S) -> Result<(), Error>
+    where
+        S: Stream<Item = Result<Ok, Error>>,
+    {
+        self.yielder.try_yield_from(stream).await
+    }
+}
+
+impl<Ok, Error> DynYield<Result<Ok, Error>> for LocalTryYielder<'_, Ok, Error> {
+    #[inline]
+    fn poll_yield(
+        &mut self,
+        cx: &mut task::Context<'_>,
+        item: &mut Option<Result<Ok, Error>>,
+    ) -> Poll<()> {
+        self.yielder.poll_yield(cx, item)
+    }
+}
@@ -0,0 +1,131 @@
+use crate::{mapped_yielder::MappedYielder, DynYield, YieldError};
+use core::task::{self, Poll};
+
+/// Handle to allow you to yield something from the try stream, transforming it into the item type of the stream
+///
+/// Created via [`TryYielder::map_ok`](crate::TryYielder::map_ok) or [`TryYielder::map_err`](crate::TryYielder::map_err)
+pub struct MappedTryYielder<'a, Ok, Error, ParentOk, ParentError, F> {
+    yielder: MappedYielder<'a, Result<Ok, Error>, Result<ParentOk, ParentError>, F>,
+}
+
+impl<'a, Ok, Error, ParentOk, ParentError, F>
+    MappedTryYielder<'a, Ok, Error, ParentOk, ParentError, F>
+where
+    F: FnMut(Result<Ok, Error>) -> Result<ParentOk, ParentError>,
+{
+    #[inline]
+    pub(crate) fn new(
+        yielder: MappedYielder<'a, Result<Ok, Error>, Result<ParentOk, ParentError>, F>,
+    ) -> Self {
+        Self { yielder }
+    }
+
+    /// Yield a success value from the stream
+    #[inline]
+    pub async fn yield_ok(&mut self, item: Ok) {
+        self.yielder.yield_item(Ok(item)).await;
+    }
+
+    /// Yield an error value from the stream
+    #[inline]
+    pub async fn yield_error(&mut self, item: Error) {
+        self.yielder.yield_item(Err(item)).await;
+    }
+
+    /// Yield a success value from the stream without panicking
+    ///
+    /// # Errors
+    ///
+    /// See [`MappedYielder::try_yield_item`]
+    #[inline]
+    pub async fn try_yield_ok(&mut self, item: Ok) -> Result<(), YieldError<Ok>> {
+        self.yielder
+            .try_yield_item(Ok(item))
+            .await
+            .map_err(|error| {
+                error.map(|item| match item {
+                    Ok(item) => item,
+                    Err(..) => unreachable!(),
+                })
+            })
+    }
+
+    /// Yield an error value from the stream without panicking
+    ///
+    /// # Errors
+    ///
+    /// See [`MappedYielder::try_yield_item`]
+    #[inline]
+    pub async fn try_yield_error(&mut self, item: Error) -> Result<(), YieldError<Error>> {
+        self.yielder
+            .try_yield_item(Err(item))
+            .await
+            .map_err(|error| {
+                error.map(|item| match item {
+                    Ok(..) => unreachable!(),
+                    Err(item) => item,
+                })
+            })
+    }
+
+    /// Borrow a yielder which transforms its success values before passing them to this one
+    #[inline]
+    #[allow(clippy::type_complexity)]
+    pub fn map_ok<'b, Inner, G>(
+        &'b mut self,
+        mut func: G,
+    ) -> MappedTryYielder<
+        'b,
+        Inner,
+        Error,
+        ParentOk,
+        ParentError,
+        impl FnMut(Result<Inner, Error>) -> Result<ParentOk, ParentError> + 'b,
+    >
+    where
+        G: FnMut(Inner) -> Ok + 'b,
+    {
+        MappedTryYielder::new(
+            self.yielder
+                .map(move |item: Result<Inner, Error>| item.map(&mut func)),
+        )
+    }
+
+    /// Borrow a yielder which transforms its error values before passing them to this one
+    #[inline]
+    #[allow(clippy::type_complexity)]
+    pub fn map_err<'b, Inner, G>(
+        &'b mut self,
+        mut func: G,
+    ) -> MappedTryYielder<
+        'b,
+        Ok,
+        Inner,
+        ParentOk,
+        ParentError,
+        impl FnMut(Result<Ok, Inner>) -> Result<ParentOk, ParentError> + 'b,
+    >
+    where
+        G: FnMut(Inner) -> Error + 'b,
+    {
+        MappedTryYielder::new(
+            self.yielder
+                .map(move |item: Result<Ok, Inner>| item.map_err(&mut func)),
+        )
+    }
+}
+
+impl<Ok, Error, ParentOk, ParentError, F> DynYield<Result<Ok, Error>>
+    for MappedTryYielder<'_, Ok, Error, ParentOk, ParentError, F>
+where
+    F: FnMut(Result<Ok, Error>) -> Result<ParentOk, ParentError>,
+{
+    #[inline]
+    fn poll_yield(
+        &mut self,
+        cx: &mut task::Context<'_>,
+        item: &mut Option<Result<Ok, Error>>,
+    ) -> Poll<()> {
+        self.yielder.poll_yield(cx, item)
+    }
+}
@@ -0,0 +1,129 @@
+use crate::{
+    waker::{FrameStash, StreamId},
+    yielder::{try_out_slot, Yielder},
+    DynYield, YieldError,
+};
+use core::{
+    future::Future,
+    marker::PhantomData,
+    pin::Pin,
+    task::{self, Poll},
+};
+
+struct MapYieldFuture<'a, Item, F> {
+    item: Option<Item>,
+    func: &'a mut F,
+    stream_id: StreamId,
+    stash: &'a FrameStash,
+}
+
+impl<Item, Parent, F> Future for MapYieldFuture<'_, Item, F>
+where
+    F: FnMut(Item) -> Parent,
+{
+    type Output = Result<(), YieldError<Item>>;
+
+    #[inline]
+    fn poll(mut self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Self::Output> {
+        let Some(item) = self.item.take() else {
+            return Poll::Ready(Ok(()));
+        };
+
+        // only transform the item once we know it fits, so errors can hand back the original
+        match try_out_slot::<Parent>(cx, self.stream_id, Some(self.stash)) {
+            Ok(slot) => {
+                *slot = Some((self.func)(item));
+                Poll::Pending
+            }
+            Err(error) => Poll::Ready(Err(error.map(|()| item))),
+        }
+    }
+}
+
+impl<Item, F> Unpin for MapYieldFuture<'_, Item, F> {}
+
+/// Handle to allow you to yield something from the stream, transforming it into the item type of the stream
+///
+/// Created via [`Yielder::map`]
+pub struct MappedYielder<'a, Item, Parent, F> {
+    yielder: &'a mut Yielder<Parent>,
+    func: F,
+    _item: PhantomData<fn(Item)>,
+}
+
+impl<'a, Item, Parent, F> MappedYielder<'a, Item, Parent, F>
+where
+    F: FnMut(Item) -> Parent,
+{
+    #[inline]
+    pub(crate) fn new(yielder: &'a mut Yielder<Parent>, func: F) -> Self {
+        Self {
+            yielder,
+            func,
+            _item: PhantomData,
+        }
+    }
+
+    /// Yield an item from the stream
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as [`Yielder::yield_item`]
+    #[inline]
+    pub async fn yield_item(&mut self, item: Item) {
+        if let Err(error) = self.try_yield_item(item).await {
+            panic!("{error}");
+        }
+    }
+
+    /// Yield an item from the stream without panicking
+    ///
+    /// # Errors
+    ///
+    /// See [`Yielder::try_yield_item`]. The error contains the item before it was transformed
+    #[inline]
+    pub async fn try_yield_item(&mut self, item: Item) -> Result<(), YieldError<Item>> {
+        let future = MapYieldFuture {
+            item: Some(item),
+            func: &mut self.func,
+            stream_id: self.yielder.stream_id,
+            stash: &self.yielder.stash,
+        };
+
+        future.await
+    }
+
+    /// Borrow a yielder which transforms its items before passing them to this one
+    #[inline]
+    pub fn map<'b, Inner, G>(
+        &'b mut self,
+        mut func: G,
+    ) -> MappedYielder<'b, Inner, Parent, impl FnMut(Inner) -> Parent + 'b>
+    where
+        G: FnMut(Inner) -> Item + 'b,
+    {
+        let outer = &mut self.func;
+        MappedYielder::new(&mut *self.yielder, move |item| outer(func(item)))
+    }
+}
+
+impl<Item, Parent, F> DynYield<Item> for MappedYielder<'_, Item, Parent, F>
+where
+    F: FnMut(Item) -> Parent,
+{
+    #[inline]
+    fn poll_yield(&mut self, cx: &mut task::Context<'_>, item: &mut Option<Item>) -> Poll<()> {
+        let mut future = MapYieldFuture {
+            item: item.take(),
+            func: &mut self.func,
+            stream_id: self.yielder.stream_id,
+            stash: &self.yielder.stash,
+        };
+
+        match Pin::new(&mut future).poll(cx) {
+            Poll::Ready(Ok(())) => Poll::Ready(()),
+            Poll::Ready(Err(error)) => panic!("{error}"),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
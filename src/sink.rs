@@ -0,0 +1,119 @@
+use crate::{sink_receiver::SinkReceiver, stream::AsynkStrim};
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{self, Poll},
+};
+use futures_sink::Sink;
+use pin_project_lite::pin_project;
+
+/// Slot shared between the sink and its receiver
+///
+/// The sink moves the item in, the receiver takes it out and reports back whether it is waiting for more.
+pub(crate) struct SinkSlot<Item> {
+    pub item: Option<Item>,
+    pub closed: bool,
+    pub waiting: bool,
+}
+
+pin_project! {
+    /// Sink created via [`sink_fn`](crate::sink_fn)
+    ///
+    /// Once the closure has returned, all further items are discarded.
+    #[project(!Unpin)]
+    pub struct SinkFn<F, Fut, Item> {
+        #[pin]
+        state: AsynkStrim<F, Fut, ()>,
+        item: Option<Item>,
+        closed: bool,
+    }
+}
+
+impl<F, Fut, Item> SinkFn<F, Fut, Item> {
+    #[inline]
+    pub(crate) fn new(func: F) -> Self {
+        Self {
+            state: AsynkStrim::Initial { func },
+            item: None,
+            closed: false,
+        }
+    }
+}
+
+impl<F, Fut, Item, Error> SinkFn<F, Fut, Item>
+where
+    F: FnOnce(SinkReceiver<Item>) -> Fut,
+    Fut: Future<Output = Result<(), Error>>,
+{
+    /// Drive the closure until it either waits for the next item or has finished
+    #[inline]
+    fn poll_idle(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Result<(), Error>> {
+        let this = self.project();
+        let mut slot = SinkSlot {
+            item: this.item.take(),
+            closed: *this.closed,
+            waiting: false,
+        };
+
+        let poll_output = this
+            .state
+            .poll_step_with(cx, &mut slot, |func, id, _slot| func(SinkReceiver::new(id)));
+
+        *this.item = slot.item;
+
+        match poll_output {
+            Poll::Ready(result) => {
+                *this.item = None;
+                Poll::Ready(result.unwrap_or(Ok(())))
+            }
+            Poll::Pending if slot.waiting => Poll::Ready(Ok(())),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl<F, Fut, Item, Error> Sink<Item> for SinkFn<F, Fut, Item>
+where
+    F: FnOnce(SinkReceiver<Item>) -> Fut,
+    Fut: Future<Output = Result<(), Error>>,
+{
+    type Error = Error;
+
+    #[inline]
+    fn poll_ready(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.poll_idle(cx)
+    }
+
+    #[inline]
+    fn start_send(self: Pin<&mut Self>, item: Item) -> Result<(), Self::Error> {
+        let this = self.project();
+        debug_assert!(
+            this.item.is_none(),
+            "called `start_send` without `poll_ready`"
+        );
+        *this.item = Some(item);
+        Ok(())
+    }
+
+    #[inline]
+    fn poll_flush(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.poll_idle(cx)
+    }
+
+    #[inline]
+    fn poll_close(
+        mut self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        // once the flag is set, the receiver never reports to be waiting again.
+        // so we only become ready once the closure has returned.
+        *self.as_mut().project().closed = true;
+        self.poll_idle(cx)
+    }
+}
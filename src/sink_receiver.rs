@@ -0,0 +1,72 @@
+use crate::{sink::SinkSlot, waker::StreamId};
+use core::{
+    future::Future,
+    marker::PhantomData,
+    pin::Pin,
+    task::{self, Poll},
+};
+
+struct ReceiveFuture<Item> {
+    stream_id: StreamId,
+    _marker: PhantomData<Item>,
+}
+
+impl<Item> Future for ReceiveFuture<Item> {
+    type Output = Option<Item>;
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Self::Output> {
+        let frame = crate::waker::find_frame_by_id(cx, self.stream_id)
+            .expect("no matching stream frame found");
+
+        let slot = {
+            let mut slot_ptr = frame.out_ref::<SinkSlot<Item>>();
+
+            // the sink always points the frame to a valid slot
+            #[allow(unsafe_code)]
+            unsafe {
+                slot_ptr.as_mut()
+            }
+        };
+
+        if let Some(item) = slot.item.take() {
+            Poll::Ready(Some(item))
+        } else if slot.closed {
+            Poll::Ready(None)
+        } else {
+            slot.waiting = true;
+            Poll::Pending
+        }
+    }
+}
+
+impl<Item> Unpin for ReceiveFuture<Item> {}
+
+/// Handle to allow you to receive the items sent into the sink
+pub struct SinkReceiver<Item> {
+    _marker: PhantomData<Item>,
+    stream_id: StreamId,
+}
+
+impl<Item> SinkReceiver<Item> {
+    #[inline]
+    pub(crate) fn new(stream_id: StreamId) -> Self {
+        Self {
+            _marker: PhantomData,
+            stream_id,
+        }
+    }
+
+    /// Receive the next item sent into the sink
+    ///
+    /// Resolves to `None` once the sink has been closed
+    #[inline]
+    pub async fn next(&mut self) -> Option<Item> {
+        let future = ReceiveFuture {
+            stream_id: self.stream_id,
+            _marker: PhantomData,
+        };
+
+        future.await
+    }
+}
@@ -1,8 +1,9 @@
-use crate::yielder::Yielder;
+use crate::{try_yielder::TryYielder, waker::StreamId, yielder::Yielder, Fallible};
 use core::{
+    fmt,
     future::Future,
-    hint::unreachable_unchecked,
     marker::PhantomData,
+    ops::ControlFlow,
     pin::Pin,
     ptr,
     task::{self, Poll},
@@ -10,99 +11,576 @@ use core::{
 use futures_core::{FusedStream, Stream};
 use pin_project_lite::pin_project;
 
+/// State of a stream generator
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StreamState {
+    /// The closure hasn't been called yet
+    NotStarted,
+    /// The closure has been called and its future hasn't finished yet
+    Running,
+    /// The future has finished and the stream won't yield any more items
+    Finished,
+}
+
+/// Slot shared between the stream and its yielder
+///
+/// The yielder moves the item and optionally a new size hint in.
+/// The stream moves the item the consumer recycled in, the yielder takes it out again.
+pub(crate) struct StreamSlot<Item> {
+    pub item: Option<Item>,
+    pub size_hint: Option<(usize, Option<usize>)>,
+    pub recycled: Option<Item>,
+}
+
+/// Count the size hint down after an item has been yielded
 #[inline]
-pub fn init<F, Fut, Yieldr, Item>(func: F) -> impl Stream<Item = Item>
-where
-    F: FnOnce(Yieldr) -> Fut,
-    Fut: Future<Output = ()>,
-    Yieldr: From<Yielder<Item>>,
-{
-    AsynkStrim::Initial { func: Some(func) }
+fn count_down((lower, upper): (usize, Option<usize>)) -> (usize, Option<usize>) {
+    (
+        lower.saturating_sub(1),
+        upper.map(|upper| upper.saturating_sub(1)),
+    )
 }
 
+/// Function type of streams whose future has been created up front
+///
+/// Used by constructors taking an async closure, such as [`async_stream_fn`](crate::async_stream_fn).
+/// Their future owns the yielder and only lends it to the closure.
+/// The future learns the identity of its stream the first time it is polled.
+#[derive(Debug)]
+pub enum Started {}
+
 pin_project! {
-    /// IMPORTANT: Never EVER EVER create this stream in the state `Initial` with the `func` parameter set to `None`
-    /// Doing this will trigger undefined behaviour.
-    ///
-    /// IMPORTANT: Never EVER EVER construct a stream in the `MarkerStuff` state.
-    /// Doing this will trigger undefined behaviour.
     #[project = AsynkStrimProj]
+    #[project_replace = AsynkStrimProjReplace]
     #[project(!Unpin)]
-    enum AsynkStrim<F, Fut, Yieldr, Item> {
+    pub(crate) enum AsynkStrim<F, Fut, Output> {
         Initial {
-            func: Option<F>,
+            func: F,
         },
         Progress {
             #[pin]
             fut: Fut,
+            id: Option<StreamId>,
+        },
+        Output {
+            output: Output,
         },
         Done,
-        MarkerStuff {
-            _item: PhantomData<Item>,
-            _yieldr: PhantomData<Yieldr>,
+    }
+}
+
+impl<F, Fut, Output> AsynkStrim<F, Fut, Output>
+where
+    Fut: Future,
+{
+    /// Poll the future once, calling the function first if we haven't started yet
+    ///
+    /// Yielders created via `yielder` write into `slot` while the future is polled.
+    /// Once the future resolves, the state machine moves into the `Done` state and the output is returned.
+    /// Polling a finished state machine returns `Ready(None)`.
+    #[inline]
+    pub(crate) fn poll_step<Yieldr, Slot>(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+        slot: &mut Slot,
+        yielder: impl FnOnce(StreamId) -> Yieldr,
+    ) -> Poll<Option<Fut::Output>>
+    where
+        F: FnOnce(Yieldr) -> Fut,
+    {
+        self.poll_step_with(cx, slot, |func, id, _slot| func(yielder(id)))
+    }
+
+    /// Poll the future once, starting it via `start` if we haven't started yet
+    ///
+    /// Works like [`poll_step`](Self::poll_step), but leaves calling the function to `start`.
+    /// It also gets access to the slot, so it can pass values from there to the function.
+    #[inline]
+    pub(crate) fn poll_step_with<Slot>(
+        mut self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+        slot: &mut Slot,
+        start: impl FnOnce(F, StreamId, &mut Slot) -> Fut,
+    ) -> Poll<Option<Fut::Output>> {
+        if let AsynkStrimProj::Initial { .. } = self.as_mut().project() {
+            // move the function out of the state machine.
+            // if the function panics, the stream stays in the `Done` state.
+            let AsynkStrimProjReplace::Initial { func } = self.as_mut().project_replace(Self::Done)
+            else {
+                unreachable!();
+            };
+
+            // only take the address before the future exists. afterwards it may borrow from itself.
+            let id = StreamId::new(ptr::from_ref(self.as_ref().get_ref()) as usize);
+            let fut = start(func, id, slot);
+            self.set(Self::Progress { fut, id: Some(id) });
+        }
+
+        let AsynkStrimProj::Progress { fut, id } = self.as_mut().project() else {
+            return Poll::Ready(None);
+        };
+
+        // futures created up front get their identity on the first poll.
+        // the field doesn't move while the future is running, so its address works just as well.
+        let id = match *id {
+            Some(id) => id,
+            None => *id.insert(StreamId::new(ptr::from_ref(&*id) as usize)),
+        };
+
+        let poll_output = crate::waker::with_context(cx, id, slot, |cx| fut.poll(cx));
+
+        match poll_output {
+            Poll::Ready(output) => {
+                self.set(Self::Done);
+                Poll::Ready(Some(output))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+
+    /// Poll the state machine for the next item
+    ///
+    /// Once the future resolves, its output is passed to `finish`.
+    /// `Ok` values are stored as the output of the stream, `Err` values are emitted as the final item.
+    ///
+    /// Hints published by the yielder are stored in `size_hint` and counted down for every item.
+    /// The recycled item is lent to the yielder while the future is polled.
+    #[inline]
+    fn poll_next<Item>(
+        mut self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+        size_hint: &mut (usize, Option<usize>),
+        recycled: &mut Option<Item>,
+        start: impl FnOnce(F, StreamId) -> Fut,
+        finish: fn(Fut::Output) -> Result<Output, Item>,
+    ) -> Poll<Option<Item>> {
+        let mut slot = StreamSlot {
+            item: None,
+            size_hint: None,
+            recycled: recycled.take(),
+        };
+        let poll_output = self
+            .as_mut()
+            .poll_step_with(cx, &mut slot, |func, stream_id, _slot| {
+                start(func, stream_id)
+            });
+
+        *recycled = slot.recycled;
+
+        if let Some(new_hint) = slot.size_hint {
+            *size_hint = new_hint;
+        }
+
+        match (poll_output, slot.item) {
+            (Poll::Ready(Some(output)), ..) => match finish(output) {
+                Ok(output) => {
+                    self.set(Self::Output { output });
+                    Poll::Ready(None)
+                }
+                Err(item) => Poll::Ready(Some(item)),
+            },
+            (Poll::Ready(None), ..) => Poll::Ready(None),
+            (Poll::Pending, Some(item)) => {
+                *size_hint = count_down(*size_hint);
+                Poll::Ready(Some(item))
+            }
+            (Poll::Pending, None) => Poll::Pending,
         }
     }
 }
 
-impl<F, Fut, Yieldr, Item> Stream for AsynkStrim<F, Fut, Yieldr, Item>
+impl<F, Fut, Output> AsynkStrim<F, Fut, Output> {
+    #[inline]
+    fn take_output(self: Pin<&mut Self>) -> Option<Output> {
+        if !matches!(*self, Self::Output { .. }) {
+            return None;
+        }
+
+        match self.project_replace(Self::Done) {
+            AsynkStrimProjReplace::Output { output } => Some(output),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Size hint of the stream, taking the state into account
+    #[inline]
+    fn size_hint(&self, size_hint: (usize, Option<usize>)) -> (usize, Option<usize>) {
+        if self.state() == StreamState::Finished {
+            (0, Some(0))
+        } else {
+            size_hint
+        }
+    }
+
+    #[inline]
+    pub(crate) fn state(&self) -> StreamState {
+        match self {
+            Self::Initial { .. } | Self::Progress { id: None, .. } => StreamState::NotStarted,
+            Self::Progress { .. } => StreamState::Running,
+            Self::Output { .. } | Self::Done => StreamState::Finished,
+        }
+    }
+}
+
+pin_project! {
+    /// Stream created via [`stream_fn`](crate::stream_fn) or [`stream_fn_with_output`](crate::stream_fn_with_output)
+    ///
+    /// The closure is called the first time the stream is polled
+    #[project(!Unpin)]
+    pub struct StreamFn<F, Fut, Item, Yieldr = Yielder<Item>>
+    where
+        Fut: Future,
+    {
+        #[pin]
+        state: AsynkStrim<F, Fut, Fut::Output>,
+        size_hint: (usize, Option<usize>),
+        recycled: Option<Item>,
+        _item: PhantomData<(Item, Yieldr)>,
+    }
+}
+
+impl<F, Fut, Item, Yieldr> StreamFn<F, Fut, Item, Yieldr>
+where
+    Fut: Future,
+{
+    #[inline]
+    pub(crate) fn new(func: F) -> Self {
+        Self {
+            state: AsynkStrim::Initial { func },
+            size_hint: (0, None),
+            recycled: None,
+            _item: PhantomData,
+        }
+    }
+
+    /// Current state of the stream
+    #[inline]
+    pub fn state(&self) -> StreamState {
+        self.state.state()
+    }
+
+    /// Take the value the closure returned
+    ///
+    /// Returns `None` if the stream hasn't finished yet or the output has already been taken
+    #[inline]
+    #[must_use]
+    pub fn take_output(self: Pin<&mut Self>) -> Option<Fut::Output> {
+        self.project().state.take_output()
+    }
+
+    /// Hand a spent item back to the closure
+    ///
+    /// The closure takes it via [`Yielder::reuse`], so allocations get reused instead of thrown away.
+    /// Only one item is kept. If the closure hasn't taken the previous one yet, it is dropped.
+    #[inline]
+    pub fn recycle(self: Pin<&mut Self>, item: Item) {
+        *self.project().recycled = Some(item);
+    }
+}
+
+impl<F, Fut, Item, Yieldr> fmt::Debug for StreamFn<F, Fut, Item, Yieldr>
+where
+    Fut: Future,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StreamFn")
+            .field("state", &self.state())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<Fut, Item, Yieldr> StreamFn<Started, Fut, Item, Yieldr>
+where
+    Fut: Future,
+{
+    #[inline]
+    pub(crate) fn started(fut: Fut) -> Self {
+        Self {
+            state: AsynkStrim::Progress { fut, id: None },
+            size_hint: (0, None),
+            recycled: None,
+            _item: PhantomData,
+        }
+    }
+}
+
+impl<F, Fut, Item, Yieldr> StreamFn<F, Fut, Item, Yieldr>
+where
+    Fut: Future,
+{
+    #[inline]
+    fn poll_next_with(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+        start: impl FnOnce(F, StreamId) -> Fut,
+    ) -> Poll<Option<Item>> {
+        let this = self.project();
+        this.state
+            .poll_next(cx, this.size_hint, this.recycled, start, Ok)
+    }
+}
+
+impl<F, Fut, Item, Yieldr> Stream for StreamFn<F, Fut, Item, Yieldr>
 where
     F: FnOnce(Yieldr) -> Fut,
-    Fut: Future<Output = ()>,
+    Fut: Future,
     Yieldr: From<Yielder<Item>>,
 {
     type Item = Item;
 
     #[inline]
-    fn poll_next(mut self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Option<Self::Item>> {
-        let stream_address = ptr::from_ref(self.as_ref().get_ref()) as usize;
-        loop {
-            match self.as_mut().project() {
-                AsynkStrimProj::Initial { func } => {
-                    // at the end of the function we transition into the progress state.
-                    // this state is never initialized with `func` set to `None`.
-                    //
-                    // we actually only do this to be able to use `.take()` to remove the function from the future.
-                    #[allow(unsafe_code)]
-                    let func = unsafe { func.take().unwrap_unchecked() };
-                    let fut = func(<_>::from(Yielder::new(stream_address)));
-
-                    self.set(Self::Progress { fut });
-                }
-                AsynkStrimProj::Progress { fut, .. } => {
-                    let mut out = None;
-                    let poll_output =
-                        crate::waker::with_context(cx.waker(), stream_address, &mut out, |cx| {
-                            fut.poll(cx)
-                        });
-
-                    match (poll_output, out) {
-                        (Poll::Ready(()), ..) => self.set(AsynkStrim::Done),
-                        (Poll::Pending, Some(item)) => break Poll::Ready(Some(item)),
-                        (Poll::Pending, None) => break Poll::Pending,
-                    }
-                }
-                AsynkStrimProj::Done => break Poll::Ready(None),
-                AsynkStrimProj::MarkerStuff { .. } => {
-                    // the state machine will never enter this state.
-                    // documented on the state machine level.
-                    #[allow(unsafe_code)]
-                    unsafe {
-                        unreachable_unchecked()
-                    }
+    fn poll_next(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Option<Self::Item>> {
+        self.poll_next_with(cx, |func, stream_id| {
+            func(Yieldr::from(Yielder::new(stream_id)))
+        })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.state.size_hint(self.size_hint)
+    }
+}
+
+impl<F, Fut, Item, Yieldr> FusedStream for StreamFn<F, Fut, Item, Yieldr>
+where
+    F: FnOnce(Yieldr) -> Fut,
+    Fut: Future,
+    Yieldr: From<Yielder<Item>>,
+{
+    #[inline]
+    fn is_terminated(&self) -> bool {
+        self.state.state() == StreamState::Finished
+    }
+}
+
+impl<Fut, Item, Yieldr> Stream for StreamFn<Started, Fut, Item, Yieldr>
+where
+    Fut: Future,
+{
+    type Item = Item;
+
+    #[inline]
+    fn poll_next(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Option<Self::Item>> {
+        self.poll_next_with(cx, |started, _stream_id| match started {})
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.state.size_hint(self.size_hint)
+    }
+}
+
+impl<Fut, Item, Yieldr> FusedStream for StreamFn<Started, Fut, Item, Yieldr>
+where
+    Fut: Future,
+{
+    #[inline]
+    fn is_terminated(&self) -> bool {
+        self.state.state() == StreamState::Finished
+    }
+}
+
+pin_project! {
+    /// Stream created via [`fallible_stream_fn`](crate::fallible_stream_fn) or [`fallible_stream_fn_with_output`](crate::fallible_stream_fn_with_output)
+    ///
+    /// The closure is called the first time the stream is polled.
+    /// If the closure short-circuits, the residual is emitted as the final item and no output is stored.
+    /// Streams created via [`fail_fast_try_stream_fn`](crate::fail_fast_try_stream_fn) also terminate with the first yielded residual
+    #[project(!Unpin)]
+    pub struct FallibleStreamFn<F, Fut, Item, Output = (), Yieldr = Yielder<Item>> {
+        #[pin]
+        state: AsynkStrim<F, Fut, Output>,
+        size_hint: (usize, Option<usize>),
+        recycled: Option<Item>,
+        fail_fast: bool,
+        _item: PhantomData<(Item, Yieldr)>,
+    }
+}
+
+/// Stream created via [`try_stream_fn`](crate::try_stream_fn) or [`try_stream_fn_with_output`](crate::try_stream_fn_with_output)
+///
+/// If the closure returns an error, it is emitted as the final item and no output is stored
+pub type TryStreamFn<F, Fut, Ok, Error, Output = (), Yieldr = TryYielder<Ok, Error>> =
+    FallibleStreamFn<F, Fut, Result<Ok, Error>, Output, Yieldr>;
+
+impl<F, Fut, Item, Output, Yieldr> FallibleStreamFn<F, Fut, Item, Output, Yieldr> {
+    #[inline]
+    pub(crate) fn new(func: F) -> Self {
+        Self {
+            state: AsynkStrim::Initial { func },
+            size_hint: (0, None),
+            recycled: None,
+            fail_fast: false,
+            _item: PhantomData,
+        }
+    }
+
+    /// Create a stream which terminates with the first residual it yields
+    #[inline]
+    pub(crate) fn new_fail_fast(func: F) -> Self {
+        Self {
+            fail_fast: true,
+            ..Self::new(func)
+        }
+    }
+
+    /// Current state of the stream
+    #[inline]
+    pub fn state(&self) -> StreamState {
+        self.state.state()
+    }
+}
+
+impl<F, Fut, Item, Output, Yieldr> fmt::Debug for FallibleStreamFn<F, Fut, Item, Output, Yieldr> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FallibleStreamFn")
+            .field("state", &self.state())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<F, Fut, Item, Output, Yieldr> FallibleStreamFn<F, Fut, Item, Output, Yieldr>
+where
+    Fut: Future<Output = Item::WithOutput<Output>>,
+    Item: Fallible,
+{
+    /// Take the value the closure returned
+    ///
+    /// Returns `None` if the stream hasn't finished yet, the closure short-circuited or the output has already been taken
+    #[inline]
+    #[must_use]
+    pub fn take_output(self: Pin<&mut Self>) -> Option<Output> {
+        self.project().state.take_output()
+    }
+
+    /// Hand a spent success value back to the closure
+    ///
+    /// See [`StreamFn::recycle`]. Try streams take it via [`TryYielder::reuse`]
+    #[inline]
+    pub fn recycle(self: Pin<&mut Self>, output: Item::Output) {
+        *self.project().recycled = Some(Item::from_output(output));
+    }
+}
+
+impl<Fut, Item, Output, Yieldr> FallibleStreamFn<Started, Fut, Item, Output, Yieldr> {
+    #[inline]
+    pub(crate) fn started(fut: Fut) -> Self {
+        Self {
+            state: AsynkStrim::Progress { fut, id: None },
+            size_hint: (0, None),
+            recycled: None,
+            fail_fast: false,
+            _item: PhantomData,
+        }
+    }
+}
+
+impl<F, Fut, Item, Output, Yieldr> FallibleStreamFn<F, Fut, Item, Output, Yieldr>
+where
+    Fut: Future<Output = Item::WithOutput<Output>>,
+    Item: Fallible,
+{
+    #[inline]
+    fn poll_next_with(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+        start: impl FnOnce(F, StreamId) -> Fut,
+    ) -> Poll<Option<Item>> {
+        let mut this = self.project();
+        let poll_output =
+            this.state
+                .as_mut()
+                .poll_next(
+                    cx,
+                    this.size_hint,
+                    this.recycled,
+                    start,
+                    |output| match output.branch() {
+                        ControlFlow::Continue(output) => Ok(output),
+                        ControlFlow::Break(residual) => Err(Item::from_residual(residual)),
+                    },
+                );
+
+        if !*this.fail_fast {
+            return poll_output;
+        }
+
+        match poll_output {
+            Poll::Ready(Some(item)) => match item.branch() {
+                ControlFlow::Continue(output) => Poll::Ready(Some(Item::from_output(output))),
+                ControlFlow::Break(residual) => {
+                    // drop the future right away, so the generator releases its resources
+                    this.state.set(AsynkStrim::Done);
+                    Poll::Ready(Some(Item::from_residual(residual)))
                 }
-            }
+            },
+            poll_output => poll_output,
         }
     }
 }
 
-impl<F, Fut, Y, Item> FusedStream for AsynkStrim<F, Fut, Y, Item>
+impl<F, Fut, Item, Output, Yieldr> Stream for FallibleStreamFn<F, Fut, Item, Output, Yieldr>
+where
+    F: FnOnce(Yieldr) -> Fut,
+    Fut: Future<Output = Item::WithOutput<Output>>,
+    Item: Fallible,
+    Yieldr: From<Yielder<Item>>,
+{
+    type Item = Item;
+
+    #[inline]
+    fn poll_next(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Option<Self::Item>> {
+        self.poll_next_with(cx, |func, stream_id| {
+            func(Yieldr::from(Yielder::new(stream_id)))
+        })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.state.size_hint(self.size_hint)
+    }
+}
+
+impl<F, Fut, Item, Output, Yieldr> FusedStream for FallibleStreamFn<F, Fut, Item, Output, Yieldr>
+where
+    F: FnOnce(Yieldr) -> Fut,
+    Fut: Future<Output = Item::WithOutput<Output>>,
+    Item: Fallible,
+    Yieldr: From<Yielder<Item>>,
+{
+    #[inline]
+    fn is_terminated(&self) -> bool {
+        self.state.state() == StreamState::Finished
+    }
+}
+
+impl<Fut, Item, Output, Yieldr> Stream for FallibleStreamFn<Started, Fut, Item, Output, Yieldr>
+where
+    Fut: Future<Output = Item::WithOutput<Output>>,
+    Item: Fallible,
+{
+    type Item = Item;
+
+    #[inline]
+    fn poll_next(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Option<Self::Item>> {
+        self.poll_next_with(cx, |started, _stream_id| match started {})
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.state.size_hint(self.size_hint)
+    }
+}
+
+impl<Fut, Item, Output, Yieldr> FusedStream for FallibleStreamFn<Started, Fut, Item, Output, Yieldr>
 where
-    F: FnOnce(Y) -> Fut,
-    Fut: Future<Output = ()>,
-    Y: From<Yielder<Item>>,
+    Fut: Future<Output = Item::WithOutput<Output>>,
+    Item: Fallible,
 {
     #[inline]
     fn is_terminated(&self) -> bool {
-        matches!(self, Self::Done)
+        self.state.state() == StreamState::Finished
     }
 }
@@ -1,4 +1,46 @@
-use crate::yielder::Yielder;
+use crate::{
+    mapped_try_yielder::MappedTryYielder,
+    mapped_yielder::MappedYielder,
+    waker::StreamId,
+    yielder::{out_slot, Yielder},
+    DynYield, YieldError,
+};
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{self, Poll},
+};
+use futures_core::Stream;
+use pin_project_lite::pin_project;
+
+pin_project! {
+    struct TryYieldFromFuture<S> {
+        #[pin]
+        stream: S,
+        stream_id: StreamId,
+    }
+}
+
+impl<S, Ok, Error> Future for TryYieldFromFuture<S>
+where
+    S: Stream<Item = Result<Ok, Error>>,
+{
+    type Output = Result<(), Error>;
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        match this.stream.poll_next(cx) {
+            Poll::Ready(Some(Ok(item))) => {
+                *out_slot::<Result<Ok, Error>>(cx, *this.stream_id) = Some(Ok(item));
+                Poll::Pending
+            }
+            Poll::Ready(Some(Err(error))) => Poll::Ready(Err(error)),
+            Poll::Ready(None) => Poll::Ready(Ok(())),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
 
 /// Handle to allow you to yield something from the stream
 pub struct TryYielder<Ok, Error> {
@@ -17,6 +59,178 @@ impl<Ok, Error> TryYielder<Ok, Error> {
     pub async fn yield_error(&mut self, item: Error) {
         self.yielder.yield_item(Err(item)).await;
     }
+
+    /// Yield a result from the stream
+    #[inline]
+    pub async fn yield_result(&mut self, result: Result<Ok, Error>) {
+        self.yielder.yield_item(result).await;
+    }
+
+    /// Convert the value into the error type and yield it from the stream
+    #[inline]
+    pub async fn yield_error_from<E>(&mut self, error: E)
+    where
+        E: Into<Error>,
+    {
+        self.yielder.yield_item(Err(error.into())).await;
+    }
+
+    /// Yield the error and keep running
+    ///
+    /// Returns the success value, or `None` if the error has been yielded
+    #[inline]
+    pub async fn check<T>(&mut self, result: Result<T, Error>) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(error) => {
+                self.yielder.yield_item(Err(error)).await;
+                None
+            }
+        }
+    }
+
+    /// Yield the success value or hand back the error
+    ///
+    /// # Errors
+    ///
+    /// Returns the error without yielding it, so you can propagate it with `?`
+    #[inline]
+    pub async fn try_yield(&mut self, result: Result<Ok, Error>) -> Result<(), Error> {
+        match result {
+            Ok(item) => {
+                self.yielder.yield_item(Ok(item)).await;
+                Ok(())
+            }
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Yield a success value from the stream without panicking
+    ///
+    /// # Errors
+    ///
+    /// See [`Yielder::try_yield_item`]
+    #[inline]
+    pub async fn try_yield_ok(&mut self, item: Ok) -> Result<(), YieldError<Ok>> {
+        self.yielder
+            .try_yield_item(Ok(item))
+            .await
+            .map_err(|error| {
+                error.map(|item| match item {
+                    Ok(item) => item,
+                    Err(..) => unreachable!(),
+                })
+            })
+    }
+
+    /// Yield an error value from the stream without panicking
+    ///
+    /// # Errors
+    ///
+    /// See [`Yielder::try_yield_item`]
+    #[inline]
+    pub async fn try_yield_error(&mut self, item: Error) -> Result<(), YieldError<Error>> {
+        self.yielder
+            .try_yield_item(Err(item))
+            .await
+            .map_err(|error| {
+                error.map(|item| match item {
+                    Ok(..) => unreachable!(),
+                    Err(item) => item,
+                })
+            })
+    }
+
+    /// Take the success value the consumer handed back via [`TryStreamFn::recycle`](crate::TryStreamFn::recycle)
+    ///
+    /// See [`Yielder::reuse`]
+    #[inline]
+    pub async fn reuse(&mut self) -> Option<Ok> {
+        self.yielder.reuse().await.and_then(Result::ok)
+    }
+
+    /// Publish how many items the stream is going to yield from now on
+    ///
+    /// See [`Yielder::set_size_hint`]
+    #[inline]
+    pub async fn set_size_hint(&mut self, lower: usize, upper: Option<usize>) {
+        self.yielder.set_size_hint(lower, upper).await;
+    }
+
+    /// Borrow a yielder which transforms its success values into the success type of the stream
+    #[inline]
+    #[allow(clippy::type_complexity)]
+    pub fn map_ok<Inner, F>(
+        &mut self,
+        mut func: F,
+    ) -> MappedTryYielder<
+        '_,
+        Inner,
+        Error,
+        Ok,
+        Error,
+        impl FnMut(Result<Inner, Error>) -> Result<Ok, Error>,
+    >
+    where
+        F: FnMut(Inner) -> Ok,
+    {
+        MappedTryYielder::new(MappedYielder::new(
+            &mut self.yielder,
+            move |item: Result<Inner, Error>| item.map(&mut func),
+        ))
+    }
+
+    /// Borrow a yielder which transforms its error values into the error type of the stream
+    #[inline]
+    #[allow(clippy::type_complexity)]
+    pub fn map_err<Inner, F>(
+        &mut self,
+        mut func: F,
+    ) -> MappedTryYielder<
+        '_,
+        Ok,
+        Inner,
+        Ok,
+        Error,
+        impl FnMut(Result<Ok, Inner>) -> Result<Ok, Error>,
+    >
+    where
+        F: FnMut(Inner) -> Error,
+    {
+        MappedTryYielder::new(MappedYielder::new(
+            &mut self.yielder,
+            move |item: Result<Ok, Inner>| item.map_err(&mut func),
+        ))
+    }
+
+    /// Yield every success value of the stream
+    ///
+    /// # Errors
+    ///
+    /// Stops at the first error and returns it without yielding it, so you can propagate it with `?`
+    #[inline]
+    pub async fn try_yield_from<S>(&mut self, stream: S) -> Result<(), Error>
+    where
+        S: Stream<Item = Result<Ok, Error>>,
+    {
+        let future = TryYieldFromFuture {
+            stream,
+            stream_id: self.yielder.stream_id,
+        };
+
+        future.await
+    }
+}
+
+impl<Ok, Error> DynYield<Result<Ok, Error>> for TryYielder<Ok, Error> {
+    #[inline]
+    fn poll_yield(
+        &mut self,
+        cx: &mut task::Context<'_>,
+        item: &mut Option<Result<Ok, Error>>,
+    ) -> Poll<()> {
+        self.yielder.poll_yield(cx, item)
+    }
 }
 
 #[doc(hidden)]
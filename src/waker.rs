@@ -1,115 +1,232 @@
+//! Frame lookup
+//!
+//! Every stream pushes a frame containing its identity and a pointer to its out slot before polling its future.
+//! Yielders find the frame of their stream by walking the frame chain.
+//!
+//! Where the chain is stored depends on the selected backend:
+//!
+//! - `backend-context-ext`: extension data of the context (nightly only)
+//! - `backend-thread-local`: thread-local stack (requires `std`)
+//! - default: wrapper around the waker
+
+#[cfg(debug_assertions)]
+use core::alloc::Layout;
 use core::{
-    mem::ManuallyDrop,
     ptr::{self, NonNull},
-    task::{self, RawWaker, RawWakerVTable, Waker},
+    sync::atomic::{AtomicPtr, AtomicUsize, Ordering},
+    task,
 };
 
-#[derive(Clone, Copy)]
-pub struct StreamFrame {
-    pub address: usize,
-    pub out_ref: NonNull<()>,
-    pub prev: NonNull<Option<StreamFrame>>,
+#[cfg(feature = "backend-context-ext")]
+mod context_ext;
+#[cfg(all(feature = "backend-thread-local", not(feature = "backend-context-ext")))]
+mod thread_local;
+#[cfg(not(any(feature = "backend-thread-local", feature = "backend-context-ext")))]
+mod wrap;
+
+#[cfg(feature = "backend-context-ext")]
+pub use self::context_ext::{find_frame, unwrap_inner, with_context};
+#[cfg(all(feature = "backend-thread-local", not(feature = "backend-context-ext")))]
+pub use self::thread_local::{find_frame, unwrap_inner, with_context};
+#[cfg(not(any(feature = "backend-thread-local", feature = "backend-context-ext")))]
+pub use self::wrap::{find_frame, unwrap_inner, with_context};
+
+static GENERATION: AtomicUsize = AtomicUsize::new(0);
+
+/// Identity of a stream instantiation
+///
+/// The address alone isn't unique since a new stream can be pinned at the address of a dropped one.
+/// Pairing it with a generation makes sure leaked yielders of the old stream don't match the new one.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct StreamId {
+    address: usize,
+    generation: usize,
 }
 
-struct WakerData<'a> {
-    inner_waker: &'a Waker,
-    frame: StreamFrame,
+impl StreamId {
+    /// Draw a fresh identity for the stream at the given address
+    #[inline]
+    pub fn new(address: usize) -> Self {
+        #[cfg(target_has_atomic = "ptr")]
+        let generation = GENERATION.fetch_add(1, Ordering::Relaxed);
+
+        // no read-modify-write atomics on this target.
+        // a duplicate generation only matters if the racing streams also end up sharing an address later on.
+        #[cfg(not(target_has_atomic = "ptr"))]
+        let generation = {
+            let generation = GENERATION.load(Ordering::Relaxed);
+            GENERATION.store(generation.wrapping_add(1), Ordering::Relaxed);
+            generation
+        };
+
+        Self {
+            address,
+            generation,
+        }
+    }
 }
 
-// -- VTable stuff start --
-
-static WAKER_VTABLE: RawWakerVTable =
-    RawWakerVTable::new(waker_clone, waker_wake, waker_wake_by_ref, waker_drop);
-
-#[allow(unsafe_code)]
-unsafe fn waker_clone(data: *const ()) -> RawWaker {
-    // we are inside the waker vtable context and the void pointer points to our data struct
-    let inner_waker = unsafe { &*data.cast::<WakerData<'_>>() }
-        .inner_waker
-        .clone();
-    let inner_waker = ManuallyDrop::new(inner_waker);
-
-    RawWaker::new(inner_waker.data(), inner_waker.vtable())
+/// Out slot of a single stream
+#[derive(Clone, Copy)]
+pub struct FrameSlot {
+    pub id: StreamId,
+    out_ref: NonNull<()>,
+    #[cfg(debug_assertions)]
+    layout: Layout,
 }
 
-fn waker_wake(_data: *const ()) {
-    unreachable!();
+impl FrameSlot {
+    /// Pointer to the out slot of the stream
+    ///
+    /// In debug builds, this checks that the slot has the layout the caller expects.
+    #[inline]
+    pub fn out_ref<Slot>(&self) -> NonNull<Slot> {
+        #[cfg(debug_assertions)]
+        assert_eq!(
+            self.layout,
+            Layout::new::<Slot>(),
+            "stream frame points to a slot of a different type"
+        );
+
+        self.out_ref.cast()
+    }
 }
 
-#[allow(unsafe_code)]
-unsafe fn waker_wake_by_ref(data: *const ()) {
-    // we are inside the waker vtable context and the void pointer points to our data struct
-    let inner_waker = unsafe { &*data.cast::<WakerData<'_>>() }.inner_waker;
-    inner_waker.wake_by_ref();
+/// Link in the frame chain
+///
+/// Besides its own slot, every frame points to the slot of the outermost stream.
+/// That way yielding to the innermost or the outermost stream never walks the chain.
+#[derive(Clone, Copy)]
+pub struct StreamFrame {
+    pub slot: FrameSlot,
+    root: Option<NonNull<FrameSlot>>,
+    pub prev: NonNull<Option<StreamFrame>>,
 }
 
-fn waker_drop(_data: *const ()) {
-    unreachable!();
-}
+impl StreamFrame {
+    #[inline]
+    pub fn new<Slot>(id: StreamId, out_ref: &mut Slot, prev: &mut Option<StreamFrame>) -> Self {
+        let slot = FrameSlot {
+            id,
+            out_ref: NonNull::from(out_ref).cast(),
+            #[cfg(debug_assertions)]
+            layout: Layout::new::<Slot>(),
+        };
+
+        // derive every pointer from the same raw pointer so none of them invalidates the other.
+        let prev = NonNull::from(prev);
+
+        // the outermost frame doesn't point anywhere. its direct children point into their copy of it.
+        #[allow(unsafe_code)]
+        let root = unsafe { (*prev.as_ptr()).as_mut() }
+            .map(|prev| prev.root.unwrap_or_else(|| NonNull::from(&mut prev.slot)));
+
+        Self { slot, root, prev }
+    }
 
-// -- VTable stuff end --
+    /// Slot of the outermost stream
+    #[inline]
+    pub fn root(&self) -> FrameSlot {
+        match self.root {
+            // the pointer targets the copy of the outermost frame which lives on the stack of a `with_context` call further up
+            #[allow(unsafe_code)]
+            Some(root) => unsafe { *root.as_ref() },
+            None => self.slot,
+        }
+    }
+}
 
-#[inline]
-fn get_waker_data(waker: &Waker) -> Option<&WakerData<'_>> {
-    if *waker.vtable() != WAKER_VTABLE {
-        return None;
+/// Frame chain lent to a yielder by [`unwrap_waker_yielding`](crate::unwrap_waker_yielding)
+///
+/// Allows yielding while the context no longer carries the chain.
+/// Points to the frame for as long as it is lent, so the yielder stays `Send` and `Sync`.
+#[derive(Default)]
+pub struct FrameStash(AtomicPtr<StreamFrame>);
+
+impl FrameStash {
+    /// Frame currently lent to the stash
+    #[inline]
+    pub fn get(&self) -> Option<StreamFrame> {
+        let frame = self.0.load(Ordering::Relaxed);
+
+        // the pointer is only set while `lend` keeps the frame alive.
+        // the yielder is mutably borrowed for that whole time, so nobody else can look at it afterwards.
+        #[allow(unsafe_code)]
+        unsafe {
+            frame.as_ref().copied()
+        }
     }
 
-    // we never set the data to null or a dangling pointer.
-    #[allow(unsafe_code)]
-    let data = unsafe {
-        waker
-            .data()
-            .cast::<WakerData<'_>>()
-            .as_ref()
-            .unwrap_unchecked()
-    };
-
-    Some(data)
+    /// Lend the frame to the stash for the duration of the function
+    #[inline]
+    pub fn lend<R>(&self, frame: &StreamFrame, func: impl FnOnce() -> R) -> R {
+        struct RestoreGuard<'a> {
+            stash: &'a FrameStash,
+            prev: *mut StreamFrame,
+        }
+
+        impl Drop for RestoreGuard<'_> {
+            fn drop(&mut self) {
+                self.stash.0.store(self.prev, Ordering::Relaxed);
+            }
+        }
+
+        let _guard = RestoreGuard {
+            stash: self,
+            prev: self.0.load(Ordering::Relaxed),
+        };
+        self.0
+            .store(ptr::from_ref(frame).cast_mut(), Ordering::Relaxed);
+
+        func()
+    }
 }
 
+/// Identity of the stream currently polling us
+///
+/// Only valid when awaited directly inside the future of the stream.
 #[inline]
-pub fn unwrap_inner(waker: &Waker) -> Option<&Waker> {
-    let data = get_waker_data(waker)?;
-    Some(data.inner_waker)
+pub async fn current_stream_id() -> StreamId {
+    core::future::poll_fn(|cx| {
+        let frame = find_frame(cx).expect("no matching stream frame found");
+        task::Poll::Ready(frame.slot.id)
+    })
+    .await
 }
 
+/// Walk the frame chain until we find the frame belonging to the stream with the given identity
 #[inline]
-pub fn find_frame(waker: &Waker) -> Option<StreamFrame> {
-    let data = get_waker_data(waker)?;
-    Some(data.frame)
+pub fn find_frame_by_id(cx: &mut task::Context<'_>, stream_id: StreamId) -> Option<FrameSlot> {
+    walk_frames(find_frame(cx), stream_id)
 }
 
+/// Walk the frame chain starting at the given frame
 #[inline]
-pub fn with_context<Item, F, Output>(
-    waker: &Waker,
-    stream_address: usize,
-    out_ref: &mut Option<Item>,
-    func: F,
-) -> Output
-where
-    F: FnOnce(&mut task::Context<'_>) -> Output,
-{
-    let mut prev = find_frame(waker);
-
-    // we construct the pointers from valid references. so they definitely aren't null.
-    #[allow(unsafe_code)]
-    let data = unsafe {
-        WakerData {
-            inner_waker: waker,
-            frame: StreamFrame {
-                address: stream_address,
-                out_ref: NonNull::new_unchecked(ptr::from_mut(out_ref).cast()),
-                prev: NonNull::new_unchecked(ptr::from_mut(&mut prev)),
-            },
-        }
-    };
+pub fn walk_frames(frame: Option<StreamFrame>, stream_id: StreamId) -> Option<FrameSlot> {
+    let head = frame?;
+    if head.slot.id == stream_id {
+        return Some(head.slot);
+    }
 
-    // we only panic or proxy out to the inner waker.
-    #[allow(unsafe_code)]
-    let waker = unsafe { Waker::new(ptr::from_ref(&data).cast(), &WAKER_VTABLE) };
-    let waker = ManuallyDrop::new(waker);
+    let root = head.root();
+    if root.id == stream_id {
+        return Some(root);
+    }
+
+    // the stream sits somewhere in the middle of the chain
+    let mut frame = Some(head);
+    loop {
+        let curr_frame = frame?;
+        if curr_frame.slot.id == stream_id {
+            break Some(curr_frame.slot);
+        }
 
-    let mut context = task::Context::from_waker(&waker);
-    func(&mut context)
+        frame = {
+            // we always set this to a valid pointer to an option
+            #[allow(unsafe_code)]
+            unsafe {
+                *curr_frame.prev.as_ref()
+            }
+        };
+    }
 }
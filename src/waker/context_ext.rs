@@ -0,0 +1,35 @@
+//! `Context::ext` backend
+//!
+//! Stores the frame in the extension data of the context. The waker is passed through untouched.
+
+use super::{StreamFrame, StreamId};
+use core::task::{self, Waker};
+
+#[inline]
+pub fn unwrap_inner<'a>(cx: &'a mut task::Context<'_>) -> Option<&'a Waker> {
+    find_frame(cx)?;
+    Some(cx.waker())
+}
+
+#[inline]
+pub fn find_frame(cx: &mut task::Context<'_>) -> Option<StreamFrame> {
+    cx.ext().downcast_ref::<StreamFrame>().copied()
+}
+
+#[inline]
+pub fn with_context<Slot, F, Output>(
+    cx: &mut task::Context<'_>,
+    stream_id: StreamId,
+    out_ref: &mut Slot,
+    func: F,
+) -> Output
+where
+    F: FnOnce(&mut task::Context<'_>) -> Output,
+{
+    let mut prev = find_frame(cx);
+
+    let mut frame = StreamFrame::new(stream_id, out_ref, &mut prev);
+
+    let mut context = task::ContextBuilder::from(cx).ext(&mut frame).build();
+    func(&mut context)
+}
@@ -0,0 +1,55 @@
+//! Thread-local backend
+//!
+//! Keeps a stack of frames in a thread-local. The waker is passed through untouched.
+
+use super::{StreamFrame, StreamId};
+use core::{
+    cell::Cell,
+    task::{self, Waker},
+};
+
+std::thread_local! {
+    static CURRENT_FRAME: Cell<Option<StreamFrame>> = const { Cell::new(None) };
+}
+
+/// Restores the previous frame, even if the poll unwinds
+struct RestoreGuard {
+    prev: Option<StreamFrame>,
+}
+
+impl Drop for RestoreGuard {
+    #[inline]
+    fn drop(&mut self) {
+        CURRENT_FRAME.set(self.prev);
+    }
+}
+
+#[inline]
+pub fn unwrap_inner<'a>(cx: &'a mut task::Context<'_>) -> Option<&'a Waker> {
+    find_frame(cx)?;
+    Some(cx.waker())
+}
+
+#[inline]
+pub fn find_frame(_cx: &mut task::Context<'_>) -> Option<StreamFrame> {
+    CURRENT_FRAME.get()
+}
+
+#[inline]
+pub fn with_context<Slot, F, Output>(
+    cx: &mut task::Context<'_>,
+    stream_id: StreamId,
+    out_ref: &mut Slot,
+    func: F,
+) -> Output
+where
+    F: FnOnce(&mut task::Context<'_>) -> Output,
+{
+    let mut prev = find_frame(cx);
+    let _guard = RestoreGuard { prev };
+
+    let frame = StreamFrame::new(stream_id, out_ref, &mut prev);
+
+    CURRENT_FRAME.set(Some(frame));
+    func(cx)
+}
@@ -0,0 +1,105 @@
+//! Default backend
+//!
+//! Stores the frame inside a wrapper around the waker.
+
+use super::{StreamFrame, StreamId};
+use core::{
+    mem::ManuallyDrop,
+    ptr,
+    task::{self, RawWaker, RawWakerVTable, Waker},
+};
+
+struct WakerData<'a> {
+    inner_waker: &'a Waker,
+    frame: StreamFrame,
+}
+
+// -- VTable stuff start --
+
+static WAKER_VTABLE: RawWakerVTable =
+    RawWakerVTable::new(waker_clone, waker_wake, waker_wake_by_ref, waker_drop);
+
+#[allow(unsafe_code)]
+unsafe fn waker_clone(data: *const ()) -> RawWaker {
+    // we are inside the waker vtable context and the void pointer points to our data struct
+    let inner_waker = unsafe { &*data.cast::<WakerData<'_>>() }
+        .inner_waker
+        .clone();
+    let inner_waker = ManuallyDrop::new(inner_waker);
+
+    RawWaker::new(inner_waker.data(), inner_waker.vtable())
+}
+
+fn waker_wake(_data: *const ()) {
+    unreachable!();
+}
+
+#[allow(unsafe_code)]
+unsafe fn waker_wake_by_ref(data: *const ()) {
+    // we are inside the waker vtable context and the void pointer points to our data struct
+    let inner_waker = unsafe { &*data.cast::<WakerData<'_>>() }.inner_waker;
+    inner_waker.wake_by_ref();
+}
+
+fn waker_drop(_data: *const ()) {
+    unreachable!();
+}
+
+// -- VTable stuff end --
+
+#[inline]
+fn get_waker_data(waker: &Waker) -> Option<&WakerData<'_>> {
+    if *waker.vtable() != WAKER_VTABLE {
+        return None;
+    }
+
+    // we never set the data to null or a dangling pointer.
+    #[allow(unsafe_code)]
+    let data = unsafe {
+        waker
+            .data()
+            .cast::<WakerData<'_>>()
+            .as_ref()
+            .unwrap_unchecked()
+    };
+
+    Some(data)
+}
+
+#[inline]
+pub fn unwrap_inner<'a>(cx: &'a mut task::Context<'_>) -> Option<&'a Waker> {
+    let data = get_waker_data(cx.waker())?;
+    Some(data.inner_waker)
+}
+
+#[inline]
+pub fn find_frame(cx: &mut task::Context<'_>) -> Option<StreamFrame> {
+    let data = get_waker_data(cx.waker())?;
+    Some(data.frame)
+}
+
+#[inline]
+pub fn with_context<Slot, F, Output>(
+    cx: &mut task::Context<'_>,
+    stream_id: StreamId,
+    out_ref: &mut Slot,
+    func: F,
+) -> Output
+where
+    F: FnOnce(&mut task::Context<'_>) -> Output,
+{
+    let mut prev = find_frame(cx);
+
+    let data = WakerData {
+        inner_waker: cx.waker(),
+        frame: StreamFrame::new(stream_id, out_ref, &mut prev),
+    };
+
+    // we only panic or proxy out to the inner waker.
+    #[allow(unsafe_code)]
+    let waker = unsafe { Waker::new(ptr::from_ref(&data).cast(), &WAKER_VTABLE) };
+    let waker = ManuallyDrop::new(waker);
+
+    let mut context = task::Context::from_waker(&waker);
+    func(&mut context)
+}
@@ -0,0 +1,60 @@
+use core::fmt;
+
+/// Reason a yield failed
+///
+/// Hands the item back so you can decide what to do with it.
+#[derive(Debug, PartialEq, Eq)]
+pub enum YieldError<Item> {
+    /// None of the frames in the context belongs to the stream of the yielder
+    ///
+    /// This happens if the yielder is polled from inside another stream.
+    NoFrame(Item),
+
+    /// The context doesn't carry any frames at all
+    ///
+    /// This happens if the yielder is polled on another thread, by another executor or underneath foreign code wrapping the waker.
+    ForeignWaker(Item),
+
+    /// An item is already waiting to be yielded
+    ///
+    /// To yield from several futures at once, use [`buffered_stream_fn`](crate::buffered_stream_fn).
+    /// Its yielder queues the items and waits for the consumer instead.
+    DoubleYield(Item),
+}
+
+impl<Item> YieldError<Item> {
+    /// Take back the item that couldn't be yielded
+    #[inline]
+    pub fn into_item(self) -> Item {
+        match self {
+            Self::NoFrame(item) | Self::ForeignWaker(item) | Self::DoubleYield(item) => item,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn map<F, U>(self, func: F) -> YieldError<U>
+    where
+        F: FnOnce(Item) -> U,
+    {
+        match self {
+            Self::NoFrame(item) => YieldError::NoFrame(func(item)),
+            Self::ForeignWaker(item) => YieldError::ForeignWaker(func(item)),
+            Self::DoubleYield(item) => YieldError::DoubleYield(func(item)),
+        }
+    }
+}
+
+impl<Item> fmt::Display for YieldError<Item> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::NoFrame(..) => "no matching stream frame found",
+            Self::ForeignWaker(..) => {
+                "no matching stream frame found. the context doesn't carry any frames"
+            }
+            Self::DoubleYield(..) => "double yield. slow down, bestie",
+        })
+    }
+}
+
+impl<Item> core::error::Error for YieldError<Item> where Item: fmt::Debug {}
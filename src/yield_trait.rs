@@ -0,0 +1,87 @@
+use core::{
+    future::{self, Future},
+    task::{self, Poll},
+};
+
+/// Anything you can yield items through
+///
+/// Implemented by all yielders of this crate, so helpers don't have to pick one.
+/// Every [`DynYield`] implements this trait, including `dyn DynYield<Item>` itself.
+///
+/// # Example
+///
+/// ```
+/// # use futures_lite::stream;
+/// # use std::pin::pin;
+/// use asynk_strim::Yield;
+///
+/// async fn countdown(yielder: &mut impl Yield<Result<u32, &'static str>>) {
+///     for i in (1..=3).rev() {
+///         yielder.yield_item(Ok(i)).await;
+///     }
+/// }
+///
+/// let stream = pin!(asynk_strim::stream_fn(|mut yielder| async move {
+///     countdown(&mut yielder).await;
+/// }));
+/// assert_eq!(stream::block_on(stream).collect::<Vec<_>>(), [Ok(3), Ok(2), Ok(1)]);
+///
+/// let stream = pin!(asynk_strim::try_stream_fn(|mut yielder| async move {
+///     countdown(&mut yielder).await;
+///     Err("liftoff")
+/// }));
+/// assert_eq!(
+///     stream::block_on(stream).collect::<Vec<_>>(),
+///     [Ok(3), Ok(2), Ok(1), Err("liftoff")]
+/// );
+/// ```
+pub trait Yield<Item> {
+    /// Yield an item from the stream
+    ///
+    /// # Panics
+    ///
+    /// The yielders of this crate panic under the same conditions as [`Yielder::yield_item`](crate::Yielder::yield_item)
+    fn yield_item(&mut self, item: Item) -> impl Future<Output = ()>;
+}
+
+/// Object-safe form of [`Yield`]
+///
+/// Allows helpers to accept `&mut dyn DynYield<Item>` instead of being generic over the yielder.
+///
+/// # Example
+///
+/// ```
+/// # use futures_lite::stream;
+/// # use std::pin::pin;
+/// use asynk_strim::{DynYield, Yield};
+///
+/// async fn greet(yielder: &mut dyn DynYield<String>, name: &str) {
+///     yielder.yield_item(format!("Hello, {name}!")).await;
+/// }
+///
+/// let stream = pin!(asynk_strim::stream_fn(|mut yielder| async move {
+///     greet(&mut yielder, "Alice").await;
+///     greet(&mut yielder, "Bob").await;
+/// }));
+///
+/// let items: Vec<_> = stream::block_on(stream).collect();
+/// assert_eq!(items, ["Hello, Alice!", "Hello, Bob!"]);
+/// ```
+pub trait DynYield<Item> {
+    /// Attempt to yield the item
+    ///
+    /// Takes the item out of the option once it has been handed to the stream.
+    /// Resolves once the stream has taken it. Poll again with the same option until then.
+    fn poll_yield(&mut self, cx: &mut task::Context<'_>, item: &mut Option<Item>) -> Poll<()>;
+}
+
+impl<Item, Y> Yield<Item> for Y
+where
+    Y: DynYield<Item> + ?Sized,
+{
+    #[inline]
+    fn yield_item(&mut self, item: Item) -> impl Future<Output = ()> {
+        let mut item = Some(item);
+        future::poll_fn(move |cx| self.poll_yield(cx, &mut item))
+    }
+}
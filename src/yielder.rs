@@ -1,76 +1,544 @@
+use crate::{
+    mapped_yielder::MappedYielder,
+    stream::StreamSlot,
+    waker::{FrameStash, StreamId},
+    DynYield, Fallible, YieldError,
+};
 use core::{
     future::Future,
     marker::PhantomData,
+    ops::ControlFlow,
     pin::Pin,
     task::{self, Poll},
 };
+use futures_core::Stream;
+use pin_project_lite::pin_project;
+
+/// Find the out slot of the stream with the given identity
+///
+/// # Panics
+///
+/// Panics if there is no matching frame or the slot is already occupied
+#[inline]
+pub(crate) fn out_slot<'a, Item>(
+    cx: &'a mut task::Context<'_>,
+    stream_id: StreamId,
+) -> &'a mut Option<Item> {
+    try_out_slot(cx, stream_id, None).unwrap_or_else(|error| panic!("{error}"))
+}
+
+/// Find the out slot of the stream with the given identity
+///
+/// Falls back to the chain lent to the stash if the context doesn't contain a matching frame.
+#[inline]
+pub(crate) fn try_out_slot<'a, Item>(
+    cx: &'a mut task::Context<'_>,
+    stream_id: StreamId,
+    stash: Option<&FrameStash>,
+) -> Result<&'a mut Option<Item>, YieldError<()>> {
+    let slot = try_stream_slot(cx, stream_id, stash)?;
+    if slot.item.is_some() {
+        return Err(YieldError::DoubleYield(()));
+    }
+
+    Ok(&mut slot.item)
+}
+
+/// Find the whole slot of the stream with the given identity
+#[inline]
+fn try_stream_slot<'a, Item>(
+    cx: &'a mut task::Context<'_>,
+    stream_id: StreamId,
+    stash: Option<&FrameStash>,
+) -> Result<&'a mut StreamSlot<Item>, YieldError<()>> {
+    let head = crate::waker::find_frame(cx);
+    let lent = stash.and_then(FrameStash::get);
+
+    let frame = crate::waker::walk_frames(head, stream_id)
+        .or_else(|| crate::waker::walk_frames(lent, stream_id))
+        .ok_or(if head.is_none() && lent.is_none() {
+            YieldError::ForeignWaker(())
+        } else {
+            YieldError::NoFrame(())
+        })?;
 
-struct YieldFuture<Item> {
+    let mut slot_ptr = frame.out_ref::<StreamSlot<Item>>();
+
+    // the stream always points the frame to a valid slot.
+    // the slot lives on the stack of `poll_next` which outlives the context.
+    #[allow(unsafe_code)]
+    Ok(unsafe { slot_ptr.as_mut() })
+}
+
+struct YieldFuture<'a, Item> {
     item: Option<Item>,
-    stream_address: usize,
+    stream_id: StreamId,
+    stash: &'a FrameStash,
 }
 
-impl<Item> Future for YieldFuture<Item> {
-    type Output = ();
+impl<Item> Future for YieldFuture<'_, Item> {
+    type Output = Result<(), YieldError<Item>>;
 
     #[inline]
     fn poll(mut self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Self::Output> {
-        if self.item.is_none() {
-            return Poll::Ready(());
+        let Some(item) = self.item.take() else {
+            return Poll::Ready(Ok(()));
+        };
+
+        match try_out_slot(cx, self.stream_id, Some(self.stash)) {
+            Ok(slot) => {
+                *slot = Some(item);
+                Poll::Pending
+            }
+            Err(error) => Poll::Ready(Err(error.map(|()| item))),
         }
+    }
+}
 
-        let mut frame = crate::waker::find_frame(cx.waker());
-        let out_ptr = loop {
-            let curr_frame = frame.expect("no matching stream frame found");
-            if curr_frame.address == self.stream_address {
-                let mut out_ptr = curr_frame.out_ref.cast::<Option<Item>>();
+impl<Item> Unpin for YieldFuture<'_, Item> {}
 
-                // the pointer is _always_ initialized to `None`.
-                #[allow(unsafe_code)]
-                break unsafe { out_ptr.as_mut() };
-            }
+struct EmplaceFuture<'a, F> {
+    func: Option<F>,
+    stream_id: StreamId,
+    stash: &'a FrameStash,
+}
+
+impl<Item, F> Future for EmplaceFuture<'_, F>
+where
+    F: FnOnce() -> Item,
+{
+    type Output = Result<(), YieldError<F>>;
 
-            frame = {
-                // we always set this to a valid pointer to an option
-                #[allow(unsafe_code)]
-                unsafe {
-                    *curr_frame.prev.as_ref()
-                }
-            };
+    #[inline]
+    fn poll(mut self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Self::Output> {
+        let Some(func) = self.func.take() else {
+            return Poll::Ready(Ok(()));
         };
 
-        assert!(out_ptr.is_none(), "double yield. slow down, bestie");
-        *out_ptr = self.item.take();
+        match try_out_slot(cx, self.stream_id, Some(self.stash)) {
+            Ok(slot) => {
+                // build the item right inside the slot instead of moving it through the future
+                *slot = Some(func());
+                Poll::Pending
+            }
+            Err(error) => Poll::Ready(Err(error.map(|()| func))),
+        }
+    }
+}
+
+impl<F> Unpin for EmplaceFuture<'_, F> {}
+
+/// Poll a single yield of the item, keeping the state in the option
+///
+/// # Panics
+///
+/// Panics under the same conditions as [`Yielder::yield_item`]
+#[inline]
+fn poll_yield<Item>(
+    yielder: &Yielder<Item>,
+    cx: &mut task::Context<'_>,
+    item: &mut Option<Item>,
+) -> Poll<()> {
+    let mut future = YieldFuture {
+        item: item.take(),
+        stream_id: yielder.stream_id,
+        stash: &yielder.stash,
+    };
+
+    match Pin::new(&mut future).poll(cx) {
+        Poll::Ready(Ok(())) => Poll::Ready(()),
+        Poll::Ready(Err(error)) => panic!("{error}"),
+        Poll::Pending => Poll::Pending,
+    }
+}
+
+pin_project! {
+    struct YieldFromFuture<S> {
+        #[pin]
+        stream: S,
+        stream_id: StreamId,
+    }
+}
+
+impl<S> Future for YieldFromFuture<S>
+where
+    S: Stream,
+{
+    type Output = ();
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        match this.stream.poll_next(cx) {
+            Poll::Ready(Some(item)) => {
+                // forward the item straight into the slot. we get polled again on the next `poll_next`.
+                *out_slot(cx, *this.stream_id) = Some(item);
+                Poll::Pending
+            }
+            Poll::Ready(None) => Poll::Ready(()),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+struct YieldFromIterFuture<I> {
+    iter: I,
+    stream_id: StreamId,
+}
+
+impl<I> Future for YieldFromIterFuture<I>
+where
+    I: Iterator,
+{
+    type Output = ();
+
+    #[inline]
+    fn poll(mut self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Self::Output> {
+        match self.iter.next() {
+            Some(item) => {
+                *out_slot(cx, self.stream_id) = Some(item);
+                Poll::Pending
+            }
+            None => Poll::Ready(()),
+        }
+    }
+}
+
+impl<I> Unpin for YieldFromIterFuture<I> {}
+
+struct SizeHintFuture<Item> {
+    size_hint: (usize, Option<usize>),
+    stream_id: StreamId,
+    _marker: PhantomData<Item>,
+}
+
+impl<Item> Future for SizeHintFuture<Item> {
+    type Output = ();
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Self::Output> {
+        let slot = try_stream_slot::<Item>(cx, self.stream_id, None)
+            .unwrap_or_else(|error| panic!("{error}"));
+
+        slot.size_hint = Some(self.size_hint);
+        Poll::Ready(())
+    }
+}
+
+impl<Item> Unpin for SizeHintFuture<Item> {}
+
+struct ReuseFuture<'a, Item> {
+    stream_id: StreamId,
+    stash: &'a FrameStash,
+    _marker: PhantomData<Item>,
+}
+
+impl<Item> Future for ReuseFuture<'_, Item> {
+    type Output = Option<Item>;
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Self::Output> {
+        let slot = try_stream_slot::<Item>(cx, self.stream_id, Some(self.stash))
+            .unwrap_or_else(|error| panic!("{error}"));
 
-        Poll::Pending
+        Poll::Ready(slot.recycled.take())
     }
 }
 
-impl<Item> Unpin for YieldFuture<Item> {}
+impl<Item> Unpin for ReuseFuture<'_, Item> {}
 
 /// Handle to allow you to yield something from the stream
 pub struct Yielder<Item> {
     _marker: PhantomData<Item>,
-    stream_address: usize,
+    pub(crate) stream_id: StreamId,
+    pub(crate) stash: FrameStash,
 }
 
 impl<Item> Yielder<Item> {
     #[inline]
-    pub(crate) fn new(stream_address: usize) -> Self {
+    pub(crate) fn new(stream_id: StreamId) -> Self {
         Self {
             _marker: PhantomData,
-            stream_address,
+            stream_id,
+            stash: FrameStash::default(),
         }
     }
 
     /// Yield an item from the stream
+    ///
+    /// # Panics
+    ///
+    /// Panics if the yielder is polled outside of its stream or while another item is waiting to be yielded.
+    /// Use [`try_yield_item`](Self::try_yield_item) to handle these conditions instead.
+    ///
+    /// If you want to yield from several futures at once, use [`buffered_stream_fn`](crate::buffered_stream_fn) instead.
     #[inline]
     pub async fn yield_item(&mut self, item: Item) {
+        if let Err(error) = self.try_yield_item(item).await {
+            panic!("{error}");
+        }
+    }
+
+    /// Yield an item from the stream without panicking
+    ///
+    /// # Errors
+    ///
+    /// Returns the item inside the error if the yielder is polled outside of its stream or while another item is waiting to be yielded
+    #[inline]
+    pub async fn try_yield_item(&mut self, item: Item) -> Result<(), YieldError<Item>> {
         let future = YieldFuture {
             item: Some(item),
-            stream_address: self.stream_address,
+            stream_id: self.stream_id,
+            stash: &self.stash,
+        };
+
+        future.await
+    }
+
+    /// Yield the item returned from the closure
+    ///
+    /// The closure is only called once the stream is ready to take the item and writes it straight into the slot of the stream.
+    /// Large items aren't moved through the future of the yield this way.
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as [`yield_item`](Self::yield_item)
+    #[inline]
+    pub async fn yield_emplace<F>(&mut self, func: F)
+    where
+        F: FnOnce() -> Item,
+    {
+        if let Err(error) = self.try_yield_emplace(func).await {
+            panic!("{error}");
+        }
+    }
+
+    /// Yield the item returned from the closure without panicking
+    ///
+    /// # Errors
+    ///
+    /// Returns the uncalled closure inside the error under the same conditions as [`try_yield_item`](Self::try_yield_item)
+    #[inline]
+    pub async fn try_yield_emplace<F>(&mut self, func: F) -> Result<(), YieldError<F>>
+    where
+        F: FnOnce() -> Item,
+    {
+        let future = EmplaceFuture {
+            func: Some(func),
+            stream_id: self.stream_id,
+            stash: &self.stash,
+        };
+
+        future.await
+    }
+
+    /// Yield the item if it continues, hand back its residual otherwise
+    ///
+    /// Returns the same kind of item, so you can propagate the residual with `?`
+    #[inline]
+    pub async fn try_yield(&mut self, item: Item) -> Item::WithOutput<()>
+    where
+        Item: Fallible,
+    {
+        match item.branch() {
+            ControlFlow::Continue(output) => {
+                self.yield_item(Item::from_output(output)).await;
+                Fallible::from_output(())
+            }
+            ControlFlow::Break(residual) => Fallible::from_residual(residual),
+        }
+    }
+
+    /// Publish how many items the stream is going to yield from now on
+    ///
+    /// The stream reports the hint via [`Stream::size_hint`] and counts it down with every item it yields.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the yielder is polled outside of its stream
+    #[inline]
+    pub async fn set_size_hint(&mut self, lower: usize, upper: Option<usize>) {
+        let future = SizeHintFuture::<Item> {
+            size_hint: (lower, upper),
+            stream_id: self.stream_id,
+            _marker: PhantomData,
+        };
+
+        future.await;
+    }
+
+    /// Take the item the consumer handed back via [`StreamFn::recycle`](crate::StreamFn::recycle)
+    ///
+    /// Returns `None` if there is nothing to reuse
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use futures_lite::StreamExt;
+    /// # use std::pin::pin;
+    /// # futures_lite::future::block_on(async {
+    /// let stream = asynk_strim::stream_fn(|mut yielder| async move {
+    ///     for chunk in ["foo", "bar"] {
+    ///         let mut buffer: Vec<u8> = yielder.reuse().await.unwrap_or_default();
+    ///         buffer.clear();
+    ///         buffer.extend_from_slice(chunk.as_bytes());
+    ///         yielder.yield_item(buffer).await;
+    ///     }
+    /// });
+    ///
+    /// let mut stream = pin!(stream);
+    /// while let Some(chunk) = stream.next().await {
+    ///     println!("{}", String::from_utf8_lossy(&chunk));
+    ///     stream.as_mut().recycle(chunk);
+    /// }
+    /// # });
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the yielder is polled outside of its stream
+    #[inline]
+    pub async fn reuse(&mut self) -> Option<Item> {
+        let future = ReuseFuture::<Item> {
+            stream_id: self.stream_id,
+            stash: &self.stash,
+            _marker: PhantomData,
+        };
+
+        future.await
+    }
+
+    /// Borrow a yielder which transforms its items into the item type of the stream
+    ///
+    /// Allows helpers yielding a different item type to write straight into this stream.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use futures_lite::StreamExt;
+    /// # use std::pin::pin;
+    /// use asynk_strim::MappedYielder;
+    ///
+    /// #[derive(Debug, PartialEq)]
+    /// enum Message {
+    ///     Event(u32),
+    ///     Shutdown,
+    /// }
+    ///
+    /// async fn events<F>(yielder: &mut MappedYielder<'_, u32, Message, F>)
+    /// where
+    ///     F: FnMut(u32) -> Message,
+    /// {
+    ///     yielder.yield_item(1).await;
+    ///     yielder.yield_item(2).await;
+    /// }
+    ///
+    /// # futures_lite::future::block_on(async {
+    /// let stream = asynk_strim::stream_fn(|mut yielder| async move {
+    ///     events(&mut yielder.map(Message::Event)).await;
+    ///     yielder.yield_item(Message::Shutdown).await;
+    /// });
+    ///
+    /// let mut stream = pin!(stream);
+    /// assert_eq!(stream.next().await, Some(Message::Event(1)));
+    /// assert_eq!(stream.next().await, Some(Message::Event(2)));
+    /// assert_eq!(stream.next().await, Some(Message::Shutdown));
+    /// # });
+    /// ```
+    #[inline]
+    pub fn map<Inner, F>(&mut self, func: F) -> MappedYielder<'_, Inner, Item, F>
+    where
+        F: FnMut(Inner) -> Item,
+    {
+        MappedYielder::new(self, func)
+    }
+
+    /// Yield every item of the stream
+    ///
+    /// Resolves once the stream is exhausted
+    #[inline]
+    pub async fn yield_from<S>(&mut self, stream: S)
+    where
+        S: Stream<Item = Item>,
+    {
+        let future = YieldFromFuture {
+            stream,
+            stream_id: self.stream_id,
+        };
+
+        future.await;
+    }
+
+    /// Yield every item of the iterator
+    ///
+    /// Resolves once the iterator is exhausted
+    #[inline]
+    pub async fn yield_from_iter<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = Item>,
+    {
+        let future = YieldFromIterFuture {
+            iter: iter.into_iter(),
+            stream_id: self.stream_id,
         };
 
         future.await;
     }
 }
+
+impl<Item> DynYield<Item> for Yielder<Item> {
+    #[inline]
+    fn poll_yield(&mut self, cx: &mut task::Context<'_>, item: &mut Option<Item>) -> Poll<()> {
+        poll_yield(self, cx, item)
+    }
+}
+
+/// Handle to allow you to yield something from inside [`unwrap_waker_yielding`](crate::unwrap_waker_yielding)
+///
+/// The lifetime is bound to the call of the closure.
+/// The frame chain it yields through lives on the stack of the polling thread, so the handle must never leave it.
+pub struct UnwrappedYielder<'a, Item> {
+    yielder: &'a Yielder<Item>,
+}
+
+impl<'a, Item> UnwrappedYielder<'a, Item> {
+    #[inline]
+    pub(crate) fn new(yielder: &'a Yielder<Item>) -> Self {
+        Self { yielder }
+    }
+
+    /// Yield an item from the stream
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as [`Yielder::yield_item`]
+    #[inline]
+    pub async fn yield_item(&mut self, item: Item) {
+        if let Err(error) = self.try_yield_item(item).await {
+            panic!("{error}");
+        }
+    }
+
+    /// Yield an item from the stream without panicking
+    ///
+    /// # Errors
+    ///
+    /// See [`Yielder::try_yield_item`]
+    #[inline]
+    pub async fn try_yield_item(&mut self, item: Item) -> Result<(), YieldError<Item>> {
+        let future = YieldFuture {
+            item: Some(item),
+            stream_id: self.yielder.stream_id,
+            stash: &self.yielder.stash,
+        };
+
+        future.await
+    }
+}
+
+impl<Item> DynYield<Item> for UnwrappedYielder<'_, Item> {
+    #[inline]
+    fn poll_yield(&mut self, cx: &mut task::Context<'_>, item: &mut Option<Item>) -> Poll<()> {
+        poll_yield(self.yielder, cx, item)
+    }
+}
@@ -0,0 +1,93 @@
+use futures_lite::{future, stream};
+use std::pin::pin;
+
+#[test]
+fn yields_in_order() {
+    let stream = pin!(asynk_strim::buffered_stream_fn::<3, _, _, _>(
+        |yielder| async move {
+            for i in 0..10 {
+                yielder.yield_item(i).await;
+            }
+        }
+    ));
+
+    let items: Vec<_> = stream::block_on(stream).collect();
+    assert_eq!(items, (0..10).collect::<Vec<_>>());
+}
+
+#[test]
+fn takes_chunks() {
+    let mut stream = pin!(asynk_strim::buffered_stream_fn::<4, _, _, _>(
+        |yielder| async move {
+            for i in 0..6 {
+                yielder.yield_item(i).await;
+            }
+        }
+    ));
+
+    let mut next_chunk =
+        || future::block_on(future::poll_fn(|cx| stream.as_mut().poll_next_chunk(cx)));
+
+    let chunk = next_chunk().unwrap();
+    assert_eq!(chunk.len(), 4);
+    assert_eq!(chunk.collect::<Vec<_>>(), [0, 1, 2, 3]);
+
+    assert_eq!(next_chunk().unwrap().collect::<Vec<_>>(), [4, 5]);
+    assert!(next_chunk().is_none());
+}
+
+#[test]
+fn flushes_on_foreign_pending() {
+    let mut stream = pin!(asynk_strim::buffered_stream_fn::<8, _, _, _>(
+        |yielder| async move {
+            yielder.yield_item(1).await;
+            yielder.yield_item(2).await;
+            future::yield_now().await;
+            yielder.yield_item(3).await;
+        }
+    ));
+
+    let chunk = future::block_on(future::poll_fn(|cx| stream.as_mut().poll_next_chunk(cx)));
+    assert_eq!(chunk.unwrap().collect::<Vec<_>>(), [1, 2]);
+}
+
+#[test]
+fn concurrent_yields() {
+    let stream = pin!(asynk_strim::buffered_stream_fn::<2, _, _, _>(
+        |yielder| async move {
+            let task = |offset, yielder: asynk_strim::BufferedYielder<_, 2>| async move {
+                for i in 0..5 {
+                    yielder.yield_item(offset + i).await;
+                }
+            };
+
+            future::zip(task(0, yielder.clone()), task(100, yielder)).await;
+        }
+    ));
+
+    let items: Vec<_> = stream::block_on(stream).collect();
+    assert_eq!(items.len(), 10);
+
+    // every sub-task yields its own items in order
+    let first: Vec<_> = items.iter().copied().filter(|item| *item < 100).collect();
+    let second: Vec<_> = items.iter().copied().filter(|item| *item >= 100).collect();
+    assert_eq!(first, [0, 1, 2, 3, 4]);
+    assert_eq!(second, [100, 101, 102, 103, 104]);
+}
+
+#[test]
+fn yield_trait() {
+    use asynk_strim::{DynYield, Yield};
+
+    let stream = pin!(asynk_strim::buffered_stream_fn::<2, _, _, _>(
+        |mut yielder| async move {
+            for i in 0..5 {
+                let yielder: &mut dyn DynYield<_> = &mut yielder;
+                yielder.yield_item(i).await;
+            }
+        }
+    ));
+
+    let items: Vec<_> = stream::block_on(stream).collect();
+    assert_eq!(items, (0..5).collect::<Vec<_>>());
+}
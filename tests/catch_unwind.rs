@@ -0,0 +1,143 @@
+#![cfg(feature = "std")]
+
+use asynk_strim::{CatchUnwind, PanicPayload};
+use futures_core::{FusedStream, Stream};
+use futures_lite::{stream, StreamExt};
+use std::pin::pin;
+
+#[test]
+fn emits_hook_item() {
+    let mut stream = pin!(asynk_strim::catch_unwind_stream_fn(
+        |mut yielder| async move {
+            yielder.yield_item(1).await;
+            yielder.yield_item(2).await;
+            panic!("oh no");
+        },
+        |payload| Some(payload.downcast_ref::<&str>().map_or(0, |msg| msg.len())),
+    ));
+
+    assert_eq!(
+        stream::block_on(stream.as_mut()).collect::<Vec<_>>(),
+        [1, 2, 5]
+    );
+    assert!(stream.panicked());
+    assert!(stream.is_terminated());
+    assert_eq!(stream.size_hint(), (0, Some(0)));
+}
+
+#[test]
+fn hook_without_item() {
+    let mut called = false;
+    {
+        let stream = pin!(asynk_strim::catch_unwind_stream_fn(
+            |mut yielder| async move {
+                yielder.yield_item(1).await;
+                panic!("oh no");
+            },
+            |_payload| {
+                called = true;
+                None
+            },
+        ));
+
+        let mut stream = stream::block_on(stream);
+        assert_eq!(stream.next(), Some(1));
+        assert_eq!(stream.next(), None);
+        assert_eq!(stream.next(), None);
+    }
+
+    assert!(called);
+}
+
+#[test]
+fn panic_in_closure() {
+    #[allow(unreachable_code)]
+    let stream = pin!(asynk_strim::catch_unwind_stream_fn(
+        |_yielder| {
+            panic!("before the future even exists");
+            async {}
+        },
+        |_payload| Some(0),
+    ));
+
+    assert_eq!(stream::block_on(stream).collect::<Vec<_>>(), [0]);
+}
+
+#[test]
+fn no_panic() {
+    let mut stream = pin!(asynk_strim::catch_unwind_stream_fn(
+        |mut yielder| async move {
+            yielder.yield_item(1).await;
+            yielder.yield_item(2).await;
+        },
+        |_payload| Some(0),
+    ));
+
+    assert_eq!(
+        stream::block_on(stream.as_mut()).collect::<Vec<_>>(),
+        [1, 2]
+    );
+    assert!(!stream.panicked());
+    assert!(stream.is_terminated());
+}
+
+#[test]
+fn try_stream_payload() {
+    let stream = pin!(asynk_strim::catch_unwind_try_stream_fn(
+        |mut yielder| async move {
+            yielder.yield_ok(1).await;
+            yielder
+                .yield_error(PanicPayload::from(Box::new("recoverable")))
+                .await;
+            panic!("unrecoverable");
+        },
+    ));
+
+    let mut stream = stream::block_on(stream);
+    assert_eq!(stream.next().unwrap().unwrap(), 1);
+
+    let error = stream.next().unwrap().unwrap_err();
+    assert_eq!(error.downcast_ref::<&str>(), Some(&"recoverable"));
+
+    let error = stream.next().unwrap().unwrap_err();
+    assert_eq!(error.downcast_ref::<&str>(), Some(&"unrecoverable"));
+
+    assert!(stream.next().is_none());
+}
+
+#[test]
+fn try_stream_custom_error() {
+    #[derive(Debug, PartialEq)]
+    enum Error {
+        Failed,
+        Panicked,
+    }
+
+    impl From<PanicPayload> for Error {
+        fn from(_payload: PanicPayload) -> Self {
+            Self::Panicked
+        }
+    }
+
+    let stream = pin!(asynk_strim::catch_unwind_try_stream_fn(
+        |mut yielder| async move {
+            yielder.yield_ok(1).await;
+            yielder.yield_error(Error::Failed).await;
+            panic!("oh no");
+        },
+    ));
+
+    let items: Vec<Result<i32, Error>> = stream::block_on(stream).collect();
+    assert_eq!(items, [Ok(1), Err(Error::Failed), Err(Error::Panicked)]);
+}
+
+#[test]
+fn wraps_any_stream() {
+    let inner = stream::iter([1, 2, 3]).map(|item| {
+        assert!(item < 3, "too big");
+        item
+    });
+
+    let stream = CatchUnwind::new(inner, |_payload| Some(-1));
+    assert_eq!(stream::block_on(stream).collect::<Vec<_>>(), [1, 2, -1]);
+}
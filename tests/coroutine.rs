@@ -0,0 +1,51 @@
+use futures_lite::future;
+use std::pin::pin;
+
+#[test]
+fn passes_resume_values() {
+    let coroutine = asynk_strim::coroutine_fn(|mut yielder, start: u32| async move {
+        let mut total = start;
+        loop {
+            total += yielder.yield_item(total).await;
+            if total > 100 {
+                break;
+            }
+        }
+    });
+
+    future::block_on(async {
+        let mut coroutine = pin!(coroutine);
+        assert_eq!(coroutine.as_mut().resume(1).await, Some(1));
+        assert_eq!(coroutine.as_mut().resume(41).await, Some(42));
+        assert!(!coroutine.is_finished());
+        assert_eq!(coroutine.as_mut().resume(1000).await, None);
+        assert!(coroutine.is_finished());
+        assert_eq!(coroutine.as_mut().resume(1).await, None);
+    });
+}
+
+#[test]
+fn resume_survives_pending() {
+    let coroutine = asynk_strim::coroutine_fn(|mut yielder, _first: &str| async move {
+        let mut yielded_now = false;
+        future::poll_fn(|cx| {
+            if yielded_now {
+                std::task::Poll::Ready(())
+            } else {
+                yielded_now = true;
+                cx.waker().wake_by_ref();
+                std::task::Poll::Pending
+            }
+        })
+        .await;
+
+        let answer = yielder.yield_item("question").await;
+        yielder.yield_item(answer).await;
+    });
+
+    future::block_on(async {
+        let mut coroutine = pin!(coroutine);
+        assert_eq!(coroutine.as_mut().resume("hi").await, Some("question"));
+        assert_eq!(coroutine.as_mut().resume("answer").await, Some("answer"));
+    });
+}
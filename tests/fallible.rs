@@ -0,0 +1,100 @@
+use asynk_strim::Fallible;
+use futures_lite::stream;
+use std::{ops::ControlFlow, pin::pin};
+
+#[test]
+fn option_short_circuits() {
+    let readings = [Some(1), Some(2), None, Some(3)];
+    let stream = pin!(asynk_strim::fallible_stream_fn(|mut yielder| async move {
+        for reading in readings {
+            yielder.try_yield(reading).await?;
+        }
+
+        Some(())
+    }));
+
+    let items: Vec<_> = stream::block_on(stream).collect();
+    assert_eq!(items, [Some(1), Some(2), None]);
+}
+
+#[test]
+fn control_flow_short_circuits() {
+    let stream = pin!(asynk_strim::fallible_stream_fn(|mut yielder| async move {
+        for i in 0.. {
+            let step = if i < 3 {
+                ControlFlow::Continue(i)
+            } else {
+                ControlFlow::Break("done")
+            };
+
+            yielder.try_yield(step).await?;
+        }
+
+        ControlFlow::Continue(())
+    }));
+
+    let items: Vec<_> = stream::block_on(stream).collect();
+    assert_eq!(
+        items,
+        [
+            ControlFlow::Continue(0),
+            ControlFlow::Continue(1),
+            ControlFlow::Continue(2),
+            ControlFlow::Break("done"),
+        ]
+    );
+}
+
+#[test]
+fn stores_output() {
+    let mut stream = pin!(asynk_strim::fallible_stream_fn_with_output(
+        |mut yielder| async move {
+            yielder.yield_item(Some(1)).await;
+            yielder.yield_item(None).await;
+            Some("finished")
+        }
+    ));
+
+    let items: Vec<_> = stream::block_on(stream.as_mut()).collect();
+    assert_eq!(items, [Some(1), None]);
+    assert_eq!(stream.as_mut().take_output(), Some("finished"));
+}
+
+#[test]
+fn custom_item() {
+    #[derive(Debug, PartialEq)]
+    enum Reading {
+        Value(u32),
+        Offline,
+    }
+
+    impl Fallible for Reading {
+        type Output = u32;
+        type Residual = ();
+        type WithOutput<O> = Option<O>;
+
+        fn from_output(output: Self::Output) -> Self {
+            Self::Value(output)
+        }
+
+        fn from_residual((): Self::Residual) -> Self {
+            Self::Offline
+        }
+
+        fn branch(self) -> ControlFlow<Self::Residual, Self::Output> {
+            match self {
+                Self::Value(value) => ControlFlow::Continue(value),
+                Self::Offline => ControlFlow::Break(()),
+            }
+        }
+    }
+
+    let stream = pin!(asynk_strim::fallible_stream_fn(|mut yielder| async move {
+        yielder.try_yield(Reading::Value(7)).await?;
+        yielder.try_yield(Reading::Offline).await?;
+        yielder.try_yield(Reading::Value(8)).await
+    }));
+
+    let items: Vec<_> = stream::block_on(stream).collect();
+    assert_eq!(items, [Reading::Value(7), Reading::Offline]);
+}
@@ -0,0 +1,43 @@
+use futures_lite::future;
+use std::pin::pin;
+
+#[test]
+fn yield_integers() {
+    let mut iter = pin!(asynk_strim::iter_fn(|mut yielder| async move {
+        yielder.yield_item(1312).await;
+        yielder.yield_item(141).await;
+    }));
+
+    assert_eq!(iter.next(), Some(1312));
+    assert_eq!(iter.next(), Some(141));
+    assert_eq!(iter.next(), None);
+    assert!(iter.is_finished());
+    assert_eq!(iter.next(), None);
+}
+
+#[test]
+#[should_panic = "iterator generator awaited something other than a yield"]
+fn panics_on_foreign_pending() {
+    let iter = pin!(asynk_strim::iter_fn(|mut yielder| async move {
+        yielder.yield_item(1).await;
+        future::pending::<()>().await;
+    }));
+
+    iter.for_each(drop);
+}
+
+#[test]
+fn size_hint() {
+    let mut iter = pin!(asynk_strim::iter_fn(|mut yielder| async move {
+        yielder.set_size_hint(4, None).await;
+        for i in 0..4 {
+            yielder.yield_item(i).await;
+        }
+    }));
+
+    assert_eq!(iter.size_hint(), (0, None));
+    assert_eq!(iter.next(), Some(0));
+    assert_eq!(iter.size_hint(), (3, None));
+    assert_eq!(iter.by_ref().count(), 3);
+    assert_eq!(iter.size_hint(), (0, Some(0)));
+}
@@ -0,0 +1,131 @@
+use asynk_strim::{LendingStream, LendingYielder, StreamState};
+use futures_lite::future;
+use std::pin::pin;
+
+#[test]
+fn lends_buffer() {
+    let input = b"one two  three";
+    let stream = asynk_strim::lending_stream_fn(async |yielder: &mut LendingYielder<[u8]>| {
+        let mut buffer = pin!(yielder.buffer([0; 4096]));
+        for word in input.split(u8::is_ascii_whitespace) {
+            if word.is_empty() {
+                continue;
+            }
+
+            let n = word.len();
+            buffer.as_mut().value_mut()[..n].copy_from_slice(word);
+            buffer.as_ref().lend(|buf| &buf[..n]).await;
+        }
+    });
+
+    future::block_on(async {
+        let mut stream = pin!(stream);
+        let mut words = Vec::new();
+        let mut addresses = Vec::new();
+        while let Some(word) = stream.next().await {
+            addresses.push(word.as_ptr());
+            words.push(String::from_utf8(word.to_vec()).unwrap());
+        }
+
+        assert_eq!(words, ["one", "two", "three"]);
+        // Lent straight out of the buffer, without moving it
+        assert!(addresses.iter().all(|address| *address == addresses[0]));
+    });
+}
+
+#[test]
+fn hands_value_back() {
+    let stream = asynk_strim::lending_stream_fn(async |yielder: &mut LendingYielder<str>| {
+        let mut line = String::new();
+        for i in 0..3 {
+            line.push_str(&i.to_string());
+            line = yielder.lend(line).await;
+        }
+    });
+
+    future::block_on(async {
+        let mut stream = pin!(stream);
+        assert_eq!(stream.state(), StreamState::NotStarted);
+        assert_eq!(stream.next().await, Some("0"));
+        assert_eq!(stream.state(), StreamState::Running);
+        assert_eq!(stream.next().await, Some("01"));
+        assert_eq!(stream.next().await, Some("012"));
+        assert_eq!(stream.next().await, None);
+        assert_eq!(stream.state(), StreamState::Finished);
+        assert_eq!(stream.next().await, None);
+    });
+}
+
+#[test]
+fn dropped_lend_is_withdrawn() {
+    let stream = asynk_strim::lending_stream_fn(async |yielder: &mut LendingYielder<str>| {
+        assert!(future::poll_once(yielder.lend(String::from("withdrawn")))
+            .await
+            .is_none());
+
+        future::yield_now().await;
+        yielder.lend(String::from("kept")).await;
+    });
+
+    future::block_on(async {
+        let mut stream = pin!(stream);
+        assert_eq!(stream.next().await, Some("kept"));
+        assert_eq!(stream.next().await, None);
+    });
+}
+
+#[test]
+fn lends_moved_value() {
+    let stream = asynk_strim::lending_stream_fn(async |yielder: &mut LendingYielder<[u8]>| {
+        let mut buffer = [0; 8];
+        for i in 1..=3 {
+            buffer[0] = i;
+            buffer = yielder.lend_with(buffer, |buffer| &buffer[..1]).await;
+        }
+    });
+
+    future::block_on(async {
+        let mut stream = pin!(stream);
+        assert_eq!(stream.next().await, Some(&[1][..]));
+        assert_eq!(stream.next().await, Some(&[2][..]));
+        assert_eq!(stream.next().await, Some(&[3][..]));
+        assert_eq!(stream.next().await, None);
+    });
+}
+
+// leaks the lend future on purpose, which miri would report
+#[cfg_attr(miri, ignore)]
+#[test]
+fn leaked_lend_is_withdrawn() {
+    let stream = asynk_strim::lending_stream_fn(async |yielder: &mut LendingYielder<[u8]>| {
+        let mut buffer = pin!(yielder.buffer([1, 2, 3]));
+        let lend = Box::pin(buffer.as_ref().lend(|buf| &buf[..]));
+        let mut lend = std::mem::ManuallyDrop::new(lend);
+        assert!(future::poll_once(lend.as_mut()).await.is_none());
+
+        // Writing to the buffer takes the lent borrow back
+        buffer.as_mut().value_mut()[0] = 4;
+        future::yield_now().await;
+        buffer.as_ref().lend(|buf| &buf[..1]).await;
+    });
+
+    future::block_on(async {
+        let mut stream = pin!(stream);
+        assert_eq!(stream.next().await, Some(&[4][..]));
+        assert_eq!(stream.next().await, None);
+    });
+}
+
+#[test]
+fn is_send() {
+    fn assert_send<T: Send>(_: &T) {}
+
+    let stream = asynk_strim::lending_stream_fn(async |yielder: &mut LendingYielder<[u8]>| {
+        yielder.lend(vec![1, 2, 3]).await;
+
+        let buffer = pin!(yielder.buffer([0; 16]));
+        buffer.as_ref().lend(|buf| &buf[..4]).await;
+    });
+
+    assert_send(&stream);
+}
@@ -0,0 +1,107 @@
+use futures_lite::future;
+use futures_util::SinkExt;
+use std::{convert::Infallible, pin::pin};
+
+#[test]
+fn receives_items() {
+    let mut received = Vec::new();
+    let received_ref = &mut received;
+    let sink = asynk_strim::sink_fn(|mut receiver| async move {
+        while let Some(item) = receiver.next().await {
+            received_ref.push(item);
+        }
+
+        Ok::<_, Infallible>(())
+    });
+
+    future::block_on(async {
+        let mut sink = pin!(sink);
+        sink.send(1312).await.unwrap();
+        sink.send(141).await.unwrap();
+        sink.close().await.unwrap();
+    });
+
+    assert_eq!(received, [1312, 141]);
+}
+
+#[test]
+fn surfaces_errors() {
+    let sink = asynk_strim::sink_fn(|mut receiver| async move {
+        while let Some(item) = receiver.next().await {
+            if item > 9000 {
+                return Err("it's over 9000");
+            }
+        }
+
+        Ok(())
+    });
+
+    future::block_on(async {
+        let mut sink = pin!(sink);
+        sink.send(1).await.unwrap();
+        assert_eq!(sink.send(9001).await, Err("it's over 9000"));
+
+        // the closure has returned. everything else gets discarded.
+        sink.send(2).await.unwrap();
+        sink.close().await.unwrap();
+    });
+}
+
+#[test]
+fn batches_until_flush() {
+    let mut batches = Vec::new();
+    let batches_ref = &mut batches;
+    let sink = asynk_strim::sink_fn(|mut receiver| async move {
+        let mut batch = Vec::new();
+        while let Some(item) = receiver.next().await {
+            batch.push(item);
+            if batch.len() == 2 {
+                batches_ref.push(std::mem::take(&mut batch));
+            }
+        }
+
+        if !batch.is_empty() {
+            batches_ref.push(batch);
+        }
+
+        Ok::<_, Infallible>(())
+    });
+
+    future::block_on(async {
+        let mut sink = pin!(sink);
+        for item in 0..5 {
+            sink.feed(item).await.unwrap();
+        }
+        sink.close().await.unwrap();
+    });
+
+    assert_eq!(batches, [vec![0, 1], vec![2, 3], vec![4]]);
+}
+
+#[test]
+fn borrow_survives_pending() {
+    let mut lines = Vec::new();
+    let lines_ref = &mut lines;
+    let sink = asynk_strim::sink_fn(|mut receiver| async move {
+        let mut batch = Vec::new();
+        let batch_ref = &mut batch;
+
+        while let Some(item) = receiver.next().await {
+            // suspend while the borrow into our own state is alive
+            future::yield_now().await;
+            batch_ref.push(item);
+        }
+
+        lines_ref.extend(batch);
+        Ok::<_, Infallible>(())
+    });
+
+    future::block_on(async {
+        let mut sink = pin!(sink);
+        sink.send("first").await.unwrap();
+        sink.send("second").await.unwrap();
+        sink.close().await.unwrap();
+    });
+
+    assert_eq!(lines, ["first", "second"]);
+}
@@ -1,5 +1,6 @@
-use futures_lite::{future, stream};
-use std::pin::pin;
+use asynk_strim::{LocalYielder, YieldError, Yielder};
+use futures_lite::{future, stream, Stream, StreamExt};
+use std::{pin::pin, ptr, sync::Mutex};
 
 // for some reason futures-lite triggers a miri error. whatever.
 // we do everything right and upkeep every invariant (as shown by all the other tests passing).
@@ -71,6 +72,134 @@ fn yield_from_thread() {
     stream::block_on(stream).for_each(|_| {});
 }
 
+#[test]
+fn try_yield_from_thread() {
+    let stream = pin!(asynk_strim::strim_fn(|mut yielder| async move {
+        let result = std::thread::spawn(move || future::block_on(yielder.try_yield_item("ùwú")))
+            .join()
+            .unwrap();
+
+        assert_eq!(result, Err(YieldError::ForeignWaker("ùwú")));
+    }));
+
+    assert_eq!(stream::block_on(stream).count(), 0);
+}
+
+#[test]
+fn try_yield_from_thread_with_stream() {
+    let stream = pin!(asynk_strim::strim_fn(|mut yielder| async move {
+        let result = std::thread::spawn(move || {
+            let mut result = None;
+            {
+                let inner_stream = pin!(asynk_strim::strim_fn(|_| async {
+                    result = Some(yielder.try_yield_item("ùwú").await);
+                }));
+
+                stream::block_on(inner_stream).for_each(|()| {});
+            }
+
+            result.unwrap()
+        })
+        .join()
+        .unwrap();
+
+        assert_eq!(result, Err(YieldError::NoFrame("ùwú")));
+    }));
+
+    assert_eq!(stream::block_on(stream).count(), 0);
+}
+
+#[test]
+fn try_double_yield() {
+    let stream = pin!(asynk_strim::strim_fn(|mut yielder| async move {
+        assert!(future::poll_once(yielder.try_yield_item(1)).await.is_none());
+
+        let error = yielder.try_yield_item(2).await.unwrap_err();
+        assert_eq!(error, YieldError::DoubleYield(2));
+        assert_eq!(error.into_item(), 2);
+
+        // hand control back so the first item gets emitted
+        future::yield_now().await;
+        yielder.try_yield_item(3).await.unwrap();
+    }));
+
+    let mut stream = stream::block_on(stream);
+    assert_eq!(stream.next(), Some(1));
+    assert_eq!(stream.next(), Some(3));
+    assert_eq!(stream.next(), None);
+}
+
+#[test]
+fn rejects_stale_yielder() {
+    static LEAKED: Mutex<Option<Yielder<u32>>> = Mutex::new(None);
+
+    async fn generator(mut yielder: Yielder<u32>) {
+        let stale = LEAKED.lock().unwrap().take();
+        if let Some(mut stale) = stale {
+            // the new stream lives at the same address as the one the yielder belongs to
+            assert_eq!(
+                stale.try_yield_item(1312).await,
+                Err(YieldError::NoFrame(1312))
+            );
+            yielder.yield_item(2).await;
+        } else {
+            yielder.yield_item(1).await;
+            *LEAKED.lock().unwrap() = Some(yielder);
+        }
+    }
+
+    let mut stream = pin!(asynk_strim::stream_fn(generator));
+    let address = ptr::from_ref(&*stream);
+    assert_eq!(stream::block_on(stream.as_mut()).collect::<Vec<_>>(), [1]);
+
+    // reuse the stack slot for a fresh stream
+    stream.set(asynk_strim::stream_fn(generator));
+    assert_eq!(ptr::from_ref(&*stream), address);
+    assert_eq!(stream::block_on(stream.as_mut()).collect::<Vec<_>>(), [2]);
+}
+
+#[test]
+fn nested_yield_to_outer() {
+    let stream = pin!(asynk_strim::strim_fn(|mut outer| async move {
+        let middle = pin!(asynk_strim::strim_fn(|mut middle| async move {
+            let inner = pin!(asynk_strim::strim_fn(|mut inner| async move {
+                outer.yield_item("outer").await;
+                middle.yield_item("middle").await;
+                inner.yield_item("inner").await;
+            }));
+
+            let inner_items: Vec<_> = inner.collect().await;
+            assert_eq!(inner_items, ["inner"]);
+        }));
+
+        let middle_items: Vec<_> = middle.collect().await;
+        assert_eq!(middle_items, ["middle"]);
+    }));
+
+    let items: Vec<_> = stream::block_on(stream).collect();
+    assert_eq!(items, ["outer"]);
+}
+
+#[test]
+fn size_hint() {
+    let mut stream = pin!(asynk_strim::strim_fn(|mut yielder| async move {
+        yielder.set_size_hint(3, Some(3)).await;
+        for i in 0..3 {
+            yielder.yield_item(i).await;
+        }
+    }));
+
+    assert_eq!(stream.size_hint(), (0, None));
+
+    for (item, remaining) in [(0, 2), (1, 1), (2, 0)] {
+        assert_eq!(future::block_on(stream.next()), Some(item));
+        assert_eq!(stream.size_hint(), (remaining, Some(remaining)));
+    }
+
+    assert_eq!(future::block_on(stream.next()), None);
+    assert_eq!(stream.size_hint(), (0, Some(0)));
+}
+
 #[test]
 fn yield_integers() {
     let stream = pin!(asynk_strim::strim_fn(|mut yielder| async move {
@@ -96,3 +225,303 @@ fn yield_heap_alloc() {
     assert_eq!(stream.next(), Some(vec!["acab"]));
     assert_eq!(stream.next(), None);
 }
+
+#[test]
+fn stores_output() {
+    let mut stream = pin!(asynk_strim::stream_fn_with_output(
+        |mut yielder| async move {
+            yielder.yield_item(1).await;
+            yielder.yield_item(2).await;
+            "summary"
+        }
+    ));
+
+    assert_eq!(stream.as_mut().take_output(), None);
+
+    let items: Vec<_> = stream::block_on(stream.as_mut()).collect();
+    assert_eq!(items, [1, 2]);
+
+    assert_eq!(stream.as_mut().take_output(), Some("summary"));
+    assert_eq!(stream.as_mut().take_output(), None);
+}
+
+#[test]
+fn reports_state() {
+    use asynk_strim::StreamState;
+    use futures_core::FusedStream;
+    use futures_lite::StreamExt;
+
+    let mut stream = pin!(asynk_strim::stream_fn(|mut yielder| async move {
+        yielder.yield_item(1).await;
+    }));
+
+    assert_eq!(stream.state(), StreamState::NotStarted);
+    assert_eq!(future::block_on(stream.next()), Some(1));
+    assert_eq!(stream.state(), StreamState::Running);
+    assert!(!stream.is_terminated());
+    assert_eq!(future::block_on(stream.next()), None);
+    assert_eq!(stream.state(), StreamState::Finished);
+    assert!(stream.is_terminated());
+    assert_eq!(format!("{stream:?}"), "StreamFn { state: Finished, .. }");
+}
+
+#[test]
+fn yield_from() {
+    let stream = pin!(asynk_strim::stream_fn(|mut yielder| async move {
+        yielder.yield_item("header").await;
+        yielder
+            .yield_from(asynk_strim::stream_fn(|mut inner| async move {
+                inner.yield_item("first").await;
+                inner.yield_item("second").await;
+            }))
+            .await;
+        yielder.yield_from_iter(["third", "fourth"]).await;
+        yielder.yield_item("footer").await;
+    }));
+
+    let items: Vec<_> = stream::block_on(stream).collect();
+    assert_eq!(
+        items,
+        ["header", "first", "second", "third", "fourth", "footer"]
+    );
+}
+
+#[test]
+fn is_send() {
+    fn assert_send<T: Send>(_: &T) {}
+
+    let stream = asynk_strim::stream_fn(|mut yielder| async move {
+        yielder.yield_item(1).await;
+        asynk_strim::unwrap_waker_yielding(&mut yielder, async |mut yielder| {
+            yielder.yield_item(2).await;
+        })
+        .await;
+    });
+
+    assert_send(&stream);
+}
+
+#[test]
+fn local_stream() {
+    use asynk_strim::StreamState;
+    use futures_core::FusedStream;
+
+    let stream = asynk_strim::local_stream_fn(async |yielder: &mut LocalYielder<'_, _>| {
+        yielder.yield_item(1).await;
+        future::yield_now().await;
+        yielder.yield_item(2).await;
+    });
+
+    future::block_on(async {
+        let mut stream = pin!(stream);
+        assert_eq!(stream.state(), StreamState::NotStarted);
+        assert_eq!(stream.next().await, Some(1));
+        assert_eq!(stream.state(), StreamState::Running);
+        assert_eq!(stream.next().await, Some(2));
+        assert_eq!(stream.next().await, None);
+        assert_eq!(stream.state(), StreamState::Finished);
+        assert!(stream.is_terminated());
+    });
+}
+
+#[test]
+fn mapped_yielder() {
+    #[derive(Debug, PartialEq)]
+    enum Message {
+        Event(u32),
+        Nested(String),
+        Done,
+    }
+
+    async fn events<F>(yielder: &mut asynk_strim::MappedYielder<'_, u32, Message, F>)
+    where
+        F: FnMut(u32) -> Message,
+    {
+        yielder.yield_item(1).await;
+        yielder.yield_item(2).await;
+    }
+
+    let stream = pin!(asynk_strim::stream_fn(|mut yielder| async move {
+        events(&mut yielder.map(Message::Event)).await;
+
+        let mut events = yielder.map(Message::Nested);
+        events.map(|num: u32| num.to_string()).yield_item(3).await;
+
+        events.yield_item("late".into()).await;
+
+        yielder.yield_item(Message::Done).await;
+    }));
+
+    fn assert_send<T: Send>(_: &T) {}
+    assert_send(&stream);
+
+    let items: Vec<_> = stream::block_on(stream).collect();
+    assert_eq!(
+        items,
+        [
+            Message::Event(1),
+            Message::Event(2),
+            Message::Nested("3".into()),
+            Message::Nested("late".into()),
+            Message::Done,
+        ]
+    );
+}
+
+#[test]
+fn mapped_yielder_hands_back_original() {
+    let stream = pin!(asynk_strim::strim_fn(|mut yielder| async move {
+        let mut mapped = yielder.map(|num: u32| num * 10);
+        assert!(future::poll_once(mapped.try_yield_item(1)).await.is_none());
+
+        let error = mapped.try_yield_item(2).await.unwrap_err();
+        assert_eq!(error, YieldError::DoubleYield(2));
+
+        // hand control back so the first item gets emitted
+        future::yield_now().await;
+    }));
+
+    let items: Vec<_> = stream::block_on(stream).collect();
+    assert_eq!(items, [10]);
+}
+
+#[test]
+fn yield_trait() {
+    use asynk_strim::{DynYield, Yield};
+
+    async fn twice<Y>(yielder: &mut Y, item: u32)
+    where
+        Y: Yield<u32> + ?Sized,
+    {
+        yielder.yield_item(item).await;
+        yielder.yield_item(item).await;
+    }
+
+    async fn once(yielder: &mut dyn DynYield<u32>, item: u32) {
+        yielder.yield_item(item).await;
+    }
+
+    let stream = pin!(asynk_strim::stream_fn(
+        |mut yielder: Yielder<u32>| async move {
+            twice(&mut yielder, 1).await;
+            once(&mut yielder, 2).await;
+            twice(&mut yielder.map(|num| num * 10), 3).await;
+            once(&mut yielder.map(|num| num * 10), 4).await;
+
+            asynk_strim::unwrap_waker_yielding(&mut yielder, async |mut yielder| {
+                twice(&mut yielder as &mut dyn DynYield<u32>, 5).await;
+            })
+            .await;
+        }
+    ));
+
+    let items: Vec<_> = stream::block_on(stream).collect();
+    assert_eq!(items, [1, 1, 2, 30, 30, 40, 5, 5]);
+}
+
+#[test]
+fn async_closure() {
+    async fn header(yielder: &mut Yielder<String>) {
+        yielder.yield_item("header".into()).await;
+    }
+
+    let lines = vec!["first", "second"];
+    let stream = pin!(asynk_strim::async_stream_fn(
+        async |yielder: &mut Yielder<String>| {
+            header(yielder).await;
+            for line in &lines {
+                yielder.yield_item((*line).to_string()).await;
+            }
+        }
+    ));
+
+    let items: Vec<_> = stream::block_on(stream).collect();
+    assert_eq!(items, ["header", "first", "second"]);
+    assert_eq!(lines.len(), 2);
+}
+
+#[test]
+fn async_closure_recycle() {
+    use asynk_strim::StreamState;
+
+    let stream = asynk_strim::async_stream_fn(async |yielder: &mut Yielder<String>| {
+        for line in ["first", "next"] {
+            let mut buffer = yielder.reuse().await.unwrap_or_default();
+            buffer.clear();
+            buffer.push_str(line);
+            yielder.yield_item(buffer).await;
+        }
+    });
+
+    future::block_on(async {
+        let mut stream = pin!(stream);
+        assert_eq!(stream.state(), StreamState::NotStarted);
+
+        let first = stream.next().await.unwrap();
+        assert_eq!(stream.state(), StreamState::Running);
+
+        let address = first.as_ptr();
+        stream.as_mut().recycle(first);
+
+        let second = stream.next().await.unwrap();
+        assert_eq!(second, "next");
+        assert_eq!(second.as_ptr(), address);
+
+        assert_eq!(stream.next().await, None);
+        assert_eq!(stream.state(), StreamState::Finished);
+        assert_eq!(stream.as_mut().take_output(), Some(()));
+    });
+}
+
+#[test]
+fn yield_emplace() {
+    let stream = pin!(asynk_strim::strim_fn(|mut yielder| async move {
+        yielder.yield_emplace(|| [1_u8; 1024]).await;
+
+        assert!(future::poll_once(yielder.try_yield_emplace(|| [2; 1024]))
+            .await
+            .is_none());
+
+        // the closure isn't called if the slot is occupied
+        let error = yielder
+            .try_yield_emplace(|| -> [u8; 1024] { unreachable!() })
+            .await
+            .unwrap_err();
+        assert!(matches!(error, YieldError::DoubleYield(..)));
+
+        future::yield_now().await;
+    }));
+
+    let items: Vec<_> = stream::block_on(stream).collect();
+    assert_eq!(items, [[1; 1024], [2; 1024]]);
+}
+
+#[test]
+fn recycle() {
+    let stream = pin!(asynk_strim::strim_fn(|mut yielder| async move {
+        for i in 0..3 {
+            let mut buffer: Vec<u8> = yielder.reuse().await.unwrap_or_default();
+            buffer.clear();
+            buffer.extend_from_slice(&[i; 64]);
+            yielder.yield_item(buffer).await;
+        }
+
+        assert_eq!(yielder.reuse().await, None);
+    }));
+
+    future::block_on(async {
+        let mut stream = stream;
+        let first = stream.next().await.unwrap();
+        let allocation = first.as_ptr();
+        stream.as_mut().recycle(first);
+
+        let second = stream.next().await.unwrap();
+        assert_eq!(second, [1; 64]);
+        assert!(ptr::eq(second.as_ptr(), allocation));
+
+        // dropping the item instead of recycling it makes the generator allocate again
+        drop(second);
+        assert_eq!(stream.next().await.unwrap(), [2; 64]);
+        assert_eq!(stream.next().await, None);
+    });
+}
@@ -1,4 +1,5 @@
-use futures_lite::stream;
+use asynk_strim::{LocalTryYielder, YieldError};
+use futures_lite::{future, stream, StreamExt};
 use std::pin::pin;
 
 #[test]
@@ -18,3 +19,311 @@ fn exits_early() {
     assert_eq!(stream.next(), Some(Err("oh no")));
     assert_eq!(stream.next(), None);
 }
+
+#[test]
+fn stores_output() {
+    let mut stream = pin!(asynk_strim::try_stream_fn_with_output(
+        |mut yielder| async move {
+            yielder.yield_ok(1).await;
+            yielder.yield_error("recoverable").await;
+            Ok::<_, &str>(42)
+        }
+    ));
+
+    let items: Vec<_> = stream::block_on(stream.as_mut()).collect();
+    assert_eq!(items, [Ok(1), Err("recoverable")]);
+    assert_eq!(stream.as_mut().take_output(), Some(42));
+}
+
+#[test]
+fn no_output_on_error() {
+    let mut stream = pin!(asynk_strim::try_stream_fn_with_output(
+        |mut yielder| async move {
+            yielder.yield_ok(1).await;
+            Err::<u32, _>("oh no")
+        }
+    ));
+
+    let items: Vec<_> = stream::block_on(stream.as_mut()).collect();
+    assert_eq!(items, [Ok(1), Err("oh no")]);
+    assert_eq!(stream.as_mut().take_output(), None);
+}
+
+#[test]
+fn try_yield_from() {
+    let stream = pin!(asynk_strim::try_stream_fn(|mut yielder| async move {
+        yielder.yield_ok(1).await;
+        yielder
+            .try_yield_from(futures_lite::stream::iter([Ok(2), Err("oh no"), Ok(3)]))
+            .await?;
+
+        #[allow(unreachable_code)]
+        yielder.yield_ok(4).await;
+
+        Ok(())
+    }));
+
+    let items: Vec<_> = stream::block_on(stream).collect();
+    assert_eq!(items, [Ok(1), Ok(2), Err("oh no")]);
+}
+
+#[test]
+fn try_yield_from_thread() {
+    let stream = pin!(asynk_strim::try_stream_fn(
+        |mut yielder: asynk_strim::TryYielder<u32, &str>| async move {
+            let result = std::thread::spawn(move || {
+                futures_lite::future::block_on(yielder.try_yield_error("oh no"))
+            })
+            .join()
+            .unwrap();
+
+            assert_eq!(result, Err(YieldError::ForeignWaker("oh no")));
+            Ok::<(), &str>(())
+        }
+    ));
+
+    assert_eq!(stream::block_on(stream).count(), 0);
+}
+
+#[test]
+fn local_try_stream() {
+    let stream = pin!(asynk_strim::local_try_stream_fn(
+        async |yielder: &mut LocalTryYielder<'_, _, _>| {
+            yielder.yield_ok(1).await;
+            yielder
+                .try_yield_from(futures_lite::stream::iter([Ok(2), Err("oh no")]))
+                .await?;
+
+            Ok(())
+        }
+    ));
+
+    let items: Vec<_> = stream::block_on(stream).collect();
+    assert_eq!(items, [Ok(1), Ok(2), Err("oh no")]);
+}
+
+#[test]
+fn mapped_try_yielder() {
+    #[derive(Debug, PartialEq)]
+    enum Error {
+        Parse(std::num::ParseIntError),
+        Other(&'static str),
+    }
+
+    let stream = pin!(asynk_strim::try_stream_fn(|mut yielder| async move {
+        yielder.yield_ok(1).await;
+
+        let mut parsed = yielder.map_err(Error::Parse);
+        for input in ["2", "nope"] {
+            match input.parse::<u64>() {
+                Ok(num) => parsed.yield_ok(num).await,
+                Err(error) => parsed.yield_error(error).await,
+            }
+        }
+
+        yielder
+            .map_ok(|num: u8| u64::from(num))
+            .map_err(Error::Other)
+            .yield_error("oh no")
+            .await;
+        yielder
+            .map_ok(|num: u32| u64::from(num) * 2)
+            .yield_ok(2)
+            .await;
+
+        Ok(())
+    }));
+
+    let items: Vec<_> = stream::block_on(stream).collect();
+    assert_eq!(
+        items,
+        [
+            Ok(1),
+            Ok(2),
+            Err(Error::Parse("nope".parse::<u64>().unwrap_err())),
+            Err(Error::Other("oh no")),
+            Ok(4),
+        ]
+    );
+}
+
+#[test]
+fn yield_trait() {
+    use asynk_strim::{DynYield, Yield};
+
+    async fn forward<Y>(yielder: &mut Y, items: Vec<Result<u32, &'static str>>)
+    where
+        Y: Yield<Result<u32, &'static str>>,
+    {
+        for item in items {
+            yielder.yield_item(item).await;
+        }
+    }
+
+    let stream = pin!(asynk_strim::try_stream_fn(|mut yielder| async move {
+        forward(&mut yielder, vec![Ok(1), Err("oh no")]).await;
+
+        let yielder: &mut dyn DynYield<_> = &mut yielder.map_ok(|num: u32| num + 1);
+        yielder.yield_item(Ok(2)).await;
+
+        Ok(())
+    }));
+
+    let items: Vec<_> = stream::block_on(stream).collect();
+    assert_eq!(items, [Ok(1), Err("oh no"), Ok(3)]);
+}
+
+#[test]
+fn yield_helpers() {
+    #[derive(Debug, PartialEq)]
+    struct Error(&'static str);
+
+    impl From<&'static str> for Error {
+        fn from(msg: &'static str) -> Self {
+            Self(msg)
+        }
+    }
+
+    async fn forward(
+        yielder: &mut asynk_strim::TryYielder<u32, Error>,
+        results: [Result<u32, Error>; 2],
+    ) -> Result<(), Error> {
+        for result in results {
+            yielder.try_yield(result).await?;
+        }
+
+        Ok(())
+    }
+
+    let stream = pin!(asynk_strim::try_stream_fn(|mut yielder| async move {
+        yielder.yield_result(Ok(1)).await;
+        yielder.yield_error_from("converted").await;
+
+        assert_eq!(
+            yielder
+                .check("2".parse::<u32>().map_err(|_| Error("nan")))
+                .await,
+            Some(2)
+        );
+        assert_eq!(
+            yielder
+                .check("x".parse::<u32>().map_err(|_| Error("nan")))
+                .await,
+            None
+        );
+
+        // the yielder stays usable after a helper bailed out early
+        let error = forward(&mut yielder, [Err(Error("bail")), Ok(99)]).await;
+        assert_eq!(error, Err(Error("bail")));
+        forward(&mut yielder, [Ok(3), Ok(4)]).await?;
+
+        yielder.try_yield(Err(Error("final"))).await?;
+        yielder.yield_ok(5).await;
+
+        Ok(())
+    }));
+
+    let items: Vec<_> = stream::block_on(stream).collect();
+    assert_eq!(
+        items,
+        [
+            Ok(1),
+            Err(Error("converted")),
+            Err(Error("nan")),
+            Ok(3),
+            Ok(4),
+            Err(Error("final")),
+        ]
+    );
+}
+
+#[test]
+fn fail_fast() {
+    use futures_core::FusedStream;
+    use futures_lite::{future, StreamExt};
+    use std::cell::Cell;
+
+    struct DropGuard<'a>(&'a Cell<bool>);
+
+    impl Drop for DropGuard<'_> {
+        fn drop(&mut self) {
+            self.0.set(true);
+        }
+    }
+
+    let dropped = Cell::new(false);
+    let dropped = &dropped;
+    let mut stream = pin!(asynk_strim::fail_fast_try_stream_fn(
+        |mut yielder| async move {
+            let _guard = DropGuard(dropped);
+
+            yielder.yield_ok(1).await;
+            yielder.yield_error("oh no").await;
+            yielder.yield_ok(2).await;
+
+            Ok(())
+        }
+    ));
+
+    assert_eq!(future::block_on(stream.next()), Some(Ok(1)));
+    assert!(!dropped.get());
+
+    assert_eq!(future::block_on(stream.next()), Some(Err("oh no")));
+    assert!(dropped.get());
+    assert!(stream.is_terminated());
+    assert_eq!(stream.state(), asynk_strim::StreamState::Finished);
+    assert_eq!(stream::block_on(stream).count(), 0);
+}
+
+#[test]
+fn async_closure() {
+    use asynk_strim::TryYielder;
+
+    async fn parse(yielder: &mut TryYielder<u32, String>, input: &str) -> Result<(), String> {
+        let num = input
+            .parse()
+            .map_err(|_| format!("{input} isn't a number"))?;
+        yielder.yield_ok(num).await;
+        Ok(())
+    }
+
+    let inputs = ["1", "2", "three", "4"];
+    let stream = pin!(asynk_strim::async_try_stream_fn(
+        async |yielder: &mut TryYielder<u32, String>| {
+            for input in &inputs {
+                parse(yielder, input).await?;
+            }
+
+            Ok(())
+        }
+    ));
+
+    let items: Vec<_> = stream::block_on(stream).collect();
+    assert_eq!(items, [Ok(1), Ok(2), Err("three isn't a number".into())]);
+}
+
+#[test]
+fn recycle() {
+    let stream = pin!(asynk_strim::try_stream_fn(|mut yielder| async move {
+        for line in ["first", "second"] {
+            let mut buffer: String = yielder.reuse().await.unwrap_or_default();
+            buffer.clear();
+            buffer.push_str(line);
+            yielder.yield_ok(buffer).await;
+        }
+
+        Err::<(), _>("done")
+    }));
+
+    future::block_on(async {
+        let mut stream = stream;
+        let first = stream.next().await.unwrap().unwrap();
+        let allocation = first.as_ptr();
+        stream.as_mut().recycle(first);
+
+        let second = stream.next().await.unwrap().unwrap();
+        assert_eq!(second, "second");
+        assert!(std::ptr::eq(second.as_ptr(), allocation));
+        assert_eq!(stream.next().await, Some(Err("done")));
+    });
+}
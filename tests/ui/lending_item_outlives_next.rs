@@ -0,0 +1,16 @@
+use asynk_strim::{LendingStream, LendingYielder};
+use std::pin::pin;
+
+fn main() {
+    futures_lite::future::block_on(async {
+        let stream = asynk_strim::lending_stream_fn(async |yielder: &mut LendingYielder<str>| {
+            yielder.lend(String::from("first")).await;
+            yielder.lend(String::from("second")).await;
+        });
+
+        let mut stream = pin!(stream);
+        let first = stream.next().await;
+        let second = stream.next().await;
+        assert_ne!(first, second);
+    });
+}
@@ -0,0 +1,12 @@
+use asynk_strim::LocalTryYielder;
+
+fn main() {
+    let _outer = asynk_strim::local_try_stream_fn(async |outer: &mut LocalTryYielder<'_, &str, ()>| {
+        let _inner = asynk_strim::local_try_stream_fn(async |inner: &mut LocalTryYielder<'_, &str, ()>| {
+            core::mem::swap(outer, inner);
+            Ok(())
+        });
+
+        Ok(())
+    });
+}
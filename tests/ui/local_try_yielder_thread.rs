@@ -0,0 +1,12 @@
+use asynk_strim::LocalTryYielder;
+
+fn main() {
+    let _stream = asynk_strim::local_try_stream_fn(async |yielder: &mut LocalTryYielder<'_, &str, ()>| {
+        let job: Box<dyn FnOnce() + Send> = Box::new(move || {
+            let _future = yielder.yield_ok("ùwú");
+        });
+        std::thread::spawn(job);
+
+        Ok(())
+    });
+}
@@ -0,0 +1,8 @@
+use asynk_strim::LocalYielder;
+
+fn main() {
+    let mut leaked = None;
+    let _stream = asynk_strim::local_stream_fn(async |yielder: &mut LocalYielder<'_, &str>| {
+        leaked = Some(yielder);
+    });
+}
@@ -0,0 +1,12 @@
+use asynk_strim::LocalYielder;
+
+fn main() {
+    let _stream = asynk_strim::local_stream_fn(async |yielder: &mut LocalYielder<'_, &str>| {
+        std::thread::scope(|scope| {
+            let job: Box<dyn FnOnce() + Send + '_> = Box::new(|| {
+                let _future = yielder.yield_item("ùwú");
+            });
+            scope.spawn(job);
+        });
+    });
+}
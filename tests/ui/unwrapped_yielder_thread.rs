@@ -0,0 +1,11 @@
+fn main() {
+    let _stream = asynk_strim::stream_fn(|yielder| async move {
+        let yielder = Box::leak(Box::new(yielder));
+        asynk_strim::unwrap_waker_yielding(yielder, async |mut yielder| {
+            std::thread::spawn(move || {
+                let _future = yielder.try_yield_item(1312);
+            });
+        })
+        .await;
+    });
+}
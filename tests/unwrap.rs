@@ -1,5 +1,6 @@
 use futures_lite::{future, stream::StreamExt};
 use std::{
+    cell::Cell,
     future::Future,
     mem::ManuallyDrop,
     pin::pin,
@@ -58,10 +59,54 @@ fn is_wrapped() -> impl Future<Output = WrappedState> {
     })
 }
 
+/// Re-registers its waker whenever `will_wake` fails, like timers and I/O resources do
+struct Register<'a> {
+    cached: Option<Waker>,
+    registrations: &'a Cell<usize>,
+    polls: usize,
+}
+
+impl Future for Register<'_> {
+    type Output = ();
+
+    fn poll(mut self: std::pin::Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<()> {
+        if !self
+            .cached
+            .as_ref()
+            .is_some_and(|cached| cached.will_wake(cx.waker()))
+        {
+            self.cached = Some(cx.waker().clone());
+            self.registrations.set(self.registrations.get() + 1);
+        }
+
+        self.polls -= 1;
+        if self.polls == 0 {
+            Poll::Ready(())
+        } else {
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
+
+fn register(registrations: &Cell<usize>) -> Register<'_> {
+    Register {
+        cached: None,
+        registrations,
+        polls: 5,
+    }
+}
+
+// Only the default backend wraps the waker
+#[cfg(not(any(feature = "backend-thread-local", feature = "backend-context-ext")))]
+const STATE_INSIDE_STREAM: WrappedState = WrappedState::Inaccessible;
+#[cfg(any(feature = "backend-thread-local", feature = "backend-context-ext"))]
+const STATE_INSIDE_STREAM: WrappedState = WrappedState::Accessible;
+
 #[test]
 fn unwrap_waker() {
     let mut stream = pin!(asynk_strim::strim_fn(|mut yielder| async move {
-        // We shouldn't have access to our waker since it's wrapped in the `asynk_strim` wrapper
+        // With the default backend, we shouldn't have access to our waker since it's wrapped in the `asynk_strim` wrapper
         yielder.yield_item(is_wrapped().await).await;
 
         // Here we should have access since the function explicitly unwraps it
@@ -71,8 +116,70 @@ fn unwrap_waker() {
     }));
 
     future::block_on(wrap_waker(async move {
-        assert_eq!(stream.next().await, Some(WrappedState::Inaccessible));
+        assert_eq!(stream.next().await, Some(STATE_INSIDE_STREAM));
         assert_eq!(stream.next().await, Some(WrappedState::Accessible));
         assert_eq!(stream.next().await, None);
     }));
 }
+
+#[test]
+fn unwrap_waker_yielding() {
+    let mut stream = pin!(asynk_strim::strim_fn(|mut yielder| async move {
+        asynk_strim::unwrap_waker_yielding(&mut yielder, async |mut yielder| {
+            // The foreign waker is accessible while we can still yield
+            yielder.yield_item(is_wrapped().await).await;
+
+            // Even if the foreign code wraps the waker itself
+            wrap_waker(async {
+                yielder.yield_item(is_wrapped().await).await;
+                yielder.yield_item(is_wrapped().await).await;
+            })
+            .await;
+        })
+        .await;
+
+        yielder.yield_item(is_wrapped().await).await;
+    }));
+
+    future::block_on(wrap_waker(async move {
+        assert_eq!(stream.next().await, Some(WrappedState::Accessible));
+        assert_eq!(stream.next().await, Some(WrappedState::Accessible));
+        assert_eq!(stream.next().await, Some(WrappedState::Accessible));
+        assert_eq!(stream.next().await, Some(STATE_INSIDE_STREAM));
+        assert_eq!(stream.next().await, None);
+    }));
+}
+
+// miri doesn't guarantee a vtable to have a single address, so `will_wake` comparisons are unreliable there
+#[cfg(not(miri))]
+#[test]
+fn will_wake_inside_unwrap_waker() {
+    let registrations = Cell::new(0);
+
+    let registrations = &registrations;
+    let stream = pin!(asynk_strim::strim_fn(|mut yielder| async move {
+        asynk_strim::unwrap_waker(register(registrations)).await;
+        yielder.yield_item(()).await;
+    }));
+
+    assert_eq!(future::block_on(stream.count()), 1);
+    assert_eq!(registrations.get(), 1);
+}
+
+#[cfg(all(
+    not(miri),
+    any(feature = "backend-thread-local", feature = "backend-context-ext")
+))]
+#[test]
+fn will_wake_without_wrapper() {
+    let registrations = Cell::new(0);
+
+    let registrations = &registrations;
+    let stream = pin!(asynk_strim::strim_fn(|mut yielder| async move {
+        register(registrations).await;
+        yielder.yield_item(()).await;
+    }));
+
+    assert_eq!(future::block_on(stream.count()), 1);
+    assert_eq!(registrations.get(), 1);
+}